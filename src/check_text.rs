@@ -0,0 +1,542 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use regex::Regex;
+
+use super::configuration::Configuration;
+use super::configuration::HeadingLevels;
+use super::configuration::SlugStyle;
+use super::format_text::get_ignored_node_groups;
+use super::format_text::parse_source_file;
+use super::format_text::strip_bom;
+use super::format_text::FileKind;
+use super::format_text::ParseFileResult;
+use super::generation::collect_heading_slugs;
+use super::generation::common::visit_nodes_mut;
+use super::generation::common::Node;
+use super::generation::common::Range;
+use super::generation::common::Ranged;
+use super::generation::get_label_key;
+use super::generation::get_plain_text;
+use super::generation::get_referenced_labels;
+use super::generation::get_undefined_footnote_references;
+use super::generation::get_undefined_link_references;
+use super::generation::is_link_references_paragraph;
+use super::generation::normalize_heading_levels;
+use super::generation::HeadingSlug;
+
+/// Characters that shouldn't end a heading.
+const HEADING_TRAILING_PUNCTUATION: &str = ".,;:!。，；：！";
+
+/// A problem found in a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  /// The byte range of the problem in the file text.
+  pub range: Range,
+  /// The name of the rule that found the problem (ex. `unused-footnote`).
+  pub rule: &'static str,
+  pub message: String,
+  pub severity: DiagnosticSeverity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+  /// The file won't render as intended (ex. a reference without a definition).
+  Error,
+  /// The file renders, but doesn't follow a common convention.
+  Warning,
+}
+
+/// Checks a file for problems that formatting doesn't fix.
+///
+/// Errors:
+///
+/// - `undefined-footnote` - A footnote reference without a definition (ex. `[^label]`).
+/// - `undefined-link-reference` - A reference link or image without a definition (ex. `[text][label]`).
+/// - `undefined-fragment` - A link to a heading or HTML anchor that doesn't exist (ex. `[text](#missing)`).
+///
+/// Warnings:
+///
+/// - `unused-footnote` - A footnote definition that's never referenced.
+/// - `unused-link-reference` - A link reference definition that's never referenced.
+/// - `heading-increment` - A heading more than one level deeper than the previous heading.
+/// - `single-h1` - A level 1 heading after the first one.
+/// - `duplicate-heading` - A heading with the same text as a previous heading.
+/// - `heading-trailing-punctuation` - A heading ending in punctuation (ex. `# Title:`).
+/// - `empty-link` - A link without a destination (ex. `[text]()` or `[text](#)`).
+/// - `bare-url` - A URL that isn't in a link (ex. `https://example.com` instead of `<https://example.com>`).
+/// - `code-block-language` - A fenced code block without a language.
+/// - `heading-level` - A heading whose level changes when formatting with `headingLevels` set to `normalize`.
+///
+/// Returns the diagnostics in the order they appear in the file or an error when
+/// it failed to parse.
+pub fn check_text(file_text: &str, config: &Configuration) -> Result<Vec<Diagnostic>> {
+  let original_text = file_text;
+  let file_text = strip_bom(original_text);
+  let bom_len = original_text.len() - file_text.len();
+  let (mut source_file, markdown_text) = match parse_source_file(file_text, FileKind::Markdown, config)? {
+    ParseFileResult::IgnoreFile => return Ok(Vec::new()),
+    ParseFileResult::SourceFile(file) => file,
+  };
+
+  let mut diagnostics = get_reference_diagnostics(&mut source_file.children, markdown_text, config);
+  diagnostics.extend(get_lint_diagnostics(&mut source_file.children, markdown_text));
+  diagnostics.extend(get_fragment_diagnostics(
+    &mut source_file.children,
+    markdown_text,
+    config,
+  ));
+  if config.heading_levels == HeadingLevels::Normalize {
+    diagnostics.extend(get_heading_level_diagnostics(
+      &mut source_file.children,
+      markdown_text,
+      config,
+    ));
+  }
+  diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+  for diagnostic in diagnostics.iter_mut() {
+    diagnostic.range = diagnostic.range.start + bom_len..diagnostic.range.end + bom_len;
+  }
+  Ok(diagnostics)
+}
+
+/// Gets the anchors of the headings in a file in document order.
+///
+/// Returns an error when it failed to parse.
+pub fn get_heading_slugs(file_text: &str, config: &Configuration) -> Result<Vec<HeadingSlug>> {
+  let original_text = file_text;
+  let file_text = strip_bom(original_text);
+  let bom_len = original_text.len() - file_text.len();
+  let (mut source_file, markdown_text) = match parse_source_file(file_text, FileKind::Markdown, config)? {
+    ParseFileResult::IgnoreFile => return Ok(Vec::new()),
+    ParseFileResult::SourceFile(file) => file,
+  };
+
+  let mut slugs = collect_heading_slugs(&mut source_file.children, markdown_text, config.slug_style);
+  for slug in slugs.iter_mut() {
+    slug.range = slug.range.start + bom_len..slug.range.end + bom_len;
+  }
+  Ok(slugs)
+}
+
+fn get_reference_diagnostics(nodes: &mut [Node], file_text: &str, config: &Configuration) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  for (range, label) in get_undefined_footnote_references(nodes) {
+    diagnostics.push(Diagnostic {
+      range,
+      rule: "undefined-footnote",
+      message: format!("Footnote [^{}] has no definition.", label),
+      severity: DiagnosticSeverity::Error,
+    });
+  }
+  for (range, label) in get_undefined_link_references(file_text, config) {
+    diagnostics.push(Diagnostic {
+      range,
+      rule: "undefined-link-reference",
+      message: format!("Link reference [{}] has no definition.", label),
+      severity: DiagnosticSeverity::Error,
+    });
+  }
+
+  let labels = get_referenced_labels(nodes, file_text);
+  for node in nodes.iter() {
+    match node {
+      Node::FootnoteDefinition(definition) if !labels.footnotes.contains(&get_label_key(&definition.name)) => {
+        // only include the label (ex. `[^label]`) since the range includes the content
+        let label_end = file_text[definition.range.clone()].find(']').map(|index| index + 1);
+        let start = definition.range.start;
+        diagnostics.push(Diagnostic {
+          range: start..start + label_end.unwrap_or(definition.range.len()),
+          rule: "unused-footnote",
+          message: format!("Footnote definition [^{}] is never referenced.", definition.name),
+          severity: DiagnosticSeverity::Warning,
+        });
+      }
+      Node::Paragraph(paragraph) if is_link_references_paragraph(paragraph) => {
+        for child in paragraph.children.iter() {
+          if let Node::LinkReference(reference) = child {
+            if !labels.links.contains(&get_label_key(&reference.name)) {
+              let text = &file_text[reference.range.clone()];
+              let start = reference.range.start;
+              diagnostics.push(Diagnostic {
+                range: start..start + text.trim_end().len(),
+                rule: "unused-link-reference",
+                message: format!("Link reference definition [{}] is never referenced.", reference.name),
+                severity: DiagnosticSeverity::Warning,
+              });
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  diagnostics
+}
+
+fn get_lint_diagnostics(nodes: &mut [Node], file_text: &str) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let mut push_warning = |range: &Range, rule: &'static str, message: String| {
+    let text = &file_text[range.clone()];
+    let start = range.start;
+    diagnostics.push(Diagnostic {
+      range: start..start + text.trim_end().len(),
+      rule,
+      message,
+      severity: DiagnosticSeverity::Warning,
+    });
+  };
+  let mut last_heading_level = None;
+  let mut has_h1 = false;
+  let mut heading_texts = HashSet::new();
+  let mut link_end = 0;
+
+  visit_nodes_mut(nodes, &mut |node| match node {
+    Node::Heading(heading) => {
+      if let Some(last_level) = last_heading_level {
+        if heading.level > last_level + 1 {
+          let message = format!(
+            "Heading level {} should be at most {} after a level {} heading.",
+            heading.level,
+            last_level + 1,
+            last_level
+          );
+          push_warning(&heading.range, "heading-increment", message);
+        }
+      }
+      last_heading_level = Some(heading.level);
+      if heading.level == 1 {
+        if has_h1 {
+          push_warning(
+            &heading.range,
+            "single-h1",
+            "Only one level 1 heading should be used.".to_string(),
+          );
+        }
+        has_h1 = true;
+      }
+
+      let text = get_plain_text(&heading.children, file_text);
+      let text = text.trim();
+      if !text.is_empty() && !heading_texts.insert(text.to_string()) {
+        let message = format!("Heading \"{}\" is used more than once.", text);
+        push_warning(&heading.range, "duplicate-heading", message);
+      }
+      if let Some(c) = text
+        .chars()
+        .last()
+        .filter(|c| HEADING_TRAILING_PUNCTUATION.contains(*c))
+      {
+        let message = format!("Heading ends with punctuation \"{}\".", c);
+        push_warning(&heading.range, "heading-trailing-punctuation", message);
+      }
+    }
+    Node::InlineLink(link) => {
+      link_end = link.range.end;
+      if link.url.is_empty() || link.url == "#" {
+        push_warning(&link.range, "empty-link", "Link has no destination.".to_string());
+      }
+    }
+    Node::ReferenceLink(_)
+    | Node::ShortcutLink(_)
+    | Node::AutoLink(_)
+    | Node::WikiLink(_)
+    | Node::InlineImage(_)
+    | Node::ReferenceImage(_) => {
+      link_end = node.range().end;
+    }
+    Node::Text(text) if text.range.start >= link_end => {
+      for range in get_bare_url_ranges(&text.text) {
+        let range = text.range.start + range.start..text.range.start + range.end;
+        let message = format!("URL {} should be in a link (ex. <{0}>).", &file_text[range.clone()]);
+        push_warning(&range, "bare-url", message);
+      }
+    }
+    Node::CodeBlock(code_block) if code_block.is_fenced => {
+      if code_block.tag.as_ref().is_none_or(|tag| tag.trim().is_empty()) {
+        let message = "Fenced code block has no language.".to_string();
+        push_warning(&code_block.range, "code-block-language", message);
+      }
+    }
+    _ => {}
+  });
+  diagnostics
+}
+
+fn get_fragment_diagnostics(nodes: &mut [Node], file_text: &str, config: &Configuration) -> Vec<Diagnostic> {
+  let anchor_attribute = Regex::new(r#"\b(?:id|name)\s*=\s*["']([^"']+)["']"#).unwrap();
+  let mut anchors = collect_heading_slugs(nodes, file_text, config.slug_style)
+    .into_iter()
+    .map(|heading| heading.slug)
+    .collect::<HashSet<_>>();
+  if config.slug_style == SlugStyle::Github {
+    anchors.insert("top".to_string());
+  }
+  let mut fragment_links = Vec::new();
+  visit_nodes_mut(nodes, &mut |node| match node {
+    Node::Html(html) => {
+      for captures in anchor_attribute.captures_iter(&file_text[html.range.clone()]) {
+        anchors.insert(captures[1].to_string());
+      }
+    }
+    Node::InlineLink(link) => fragment_links.push((link.range.clone(), link.url.clone())),
+    Node::LinkReference(reference) => fragment_links.push((reference.range.clone(), reference.link.clone())),
+    _ => {}
+  });
+
+  let mut diagnostics = Vec::new();
+  for (range, url) in fragment_links {
+    let Some(fragment) = url.strip_prefix('#').filter(|fragment| !fragment.is_empty()) else {
+      continue;
+    };
+    if !anchors.contains(fragment) && !anchors.contains(&percent_decode(fragment)) {
+      let text = &file_text[range.clone()];
+      diagnostics.push(Diagnostic {
+        range: range.start..range.start + text.trim_end().len(),
+        rule: "undefined-fragment",
+        message: format!("Link fragment #{} doesn't match a heading or anchor.", fragment),
+        severity: DiagnosticSeverity::Error,
+      });
+    }
+  }
+  diagnostics
+}
+
+fn get_heading_level_diagnostics(nodes: &mut [Node], file_text: &str, config: &Configuration) -> Vec<Diagnostic> {
+  let ignored_groups = get_ignored_node_groups(nodes, file_text, config);
+  let is_ignored = |index: usize| ignored_groups.iter().any(|group| group.contains(&index));
+  normalize_heading_levels(nodes, config.heading_start_level, is_ignored)
+    .into_iter()
+    .map(|change| Diagnostic {
+      range: change.range.start..change.range.start + file_text[change.range.clone()].trim_end().len(),
+      rule: "heading-level",
+      message: format!(
+        "Heading level {} will be formatted as level {}.",
+        change.old_level, change.new_level
+      ),
+      severity: DiagnosticSeverity::Warning,
+    })
+    .collect()
+}
+
+/// Decodes the percent-encoded bytes in a URL fragment (ex. `caf%C3%A9` to `café`).
+fn percent_decode(text: &str) -> String {
+  let mut bytes = Vec::with_capacity(text.len());
+  let mut index = 0;
+  while index < text.len() {
+    let byte = text.as_bytes()[index];
+    let decoded = text
+      .get(index + 1..index + 3)
+      .filter(|_| byte == b'%')
+      .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+    match decoded {
+      Some(decoded) => {
+        bytes.push(decoded);
+        index += 3;
+      }
+      None => {
+        bytes.push(byte);
+        index += 1;
+      }
+    }
+  }
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Gets the ranges of the URLs starting with `http://` or `https://` in the text.
+fn get_bare_url_ranges(text: &str) -> Vec<Range> {
+  let mut ranges = Vec::new();
+  let mut search_start = 0;
+  while let Some(index) = text[search_start..].find("http").map(|index| search_start + index) {
+    let url_text = &text[index..];
+    let len = url_text
+      .find(|c: char| c.is_whitespace() || c == '<' || c == '>')
+      .unwrap_or(url_text.len());
+    let url = url_text[..len].trim_end_matches(|c: char| ".,:;!?'\")*_~".contains(c));
+    let is_word_start = !text[..index].ends_with(|c: char| c.is_alphanumeric());
+    let is_url = url.starts_with("http://") || url.starts_with("https://");
+    if is_word_start && is_url && !url.ends_with("//") {
+      ranges.push(index..index + url.len());
+    }
+    search_start = index + len.max(4);
+  }
+  ranges
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::configuration::ConfigurationBuilder;
+
+  fn check(file_text: &str) -> Vec<(Range, &'static str)> {
+    let config = ConfigurationBuilder::new().build();
+    check_text(file_text, &config)
+      .unwrap()
+      .into_iter()
+      .map(|diagnostic| (diagnostic.range, diagnostic.rule))
+      .collect()
+  }
+
+  #[test]
+  fn reports_undefined_references() {
+    let file_text = "Text[^a] and [^nope] with [link][Nope], [coll][], `[^code]`, and \\[^escaped].\n\n[^a]: Note.\n";
+    assert_eq!(
+      check(file_text),
+      vec![
+        (13..20, "undefined-footnote"),
+        (26..38, "undefined-link-reference"),
+        (40..48, "undefined-link-reference"),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_unused_definitions() {
+    let file_text =
+      "[Used][a], [b][], [c], and [^1].\n\n[^1]: Used.\n[^2]: Unused.\n\n[A]: a\n[B]: b\n[c]: c\n[d]: d  \n";
+    assert_eq!(
+      check(file_text),
+      vec![(46..50, "unused-footnote"), (82..88, "unused-link-reference")]
+    );
+  }
+
+  #[test]
+  fn matches_labels_ignoring_case_and_whitespace() {
+    assert_eq!(check("[text][Some  Label]\n\n[some label]: url\n"), vec![]);
+  }
+
+  #[test]
+  fn reports_heading_diagnostics() {
+    let file_text = "# Title\n\n### Skipped\n\n## Setup:\n\n# Other\n\n## *Skipped*\n\n## Setup!\n";
+    assert_eq!(
+      check(file_text),
+      vec![
+        (9..20, "heading-increment"),
+        (22..31, "heading-trailing-punctuation"),
+        (33..40, "single-h1"),
+        (42..54, "duplicate-heading"),
+        (56..65, "heading-trailing-punctuation"),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_link_diagnostics() {
+    let file_text =
+      "[a]() [b](#) [c](#c) <https://a.com> [https://b.com](https://b.com) see https://c.com/x. and `https://d.com`\n";
+    assert_eq!(
+      check(file_text),
+      vec![
+        (0..5, "empty-link"),
+        (6..12, "empty-link"),
+        (13..20, "undefined-fragment"),
+        (72..87, "bare-url"),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_code_block_without_language() {
+    let file_text = "```\ncode\n```\n\n```rust\ncode\n```\n\n    indented\n";
+    assert_eq!(check(file_text), vec![(0..12, "code-block-language")]);
+  }
+
+  #[test]
+  fn gets_bare_url_ranges() {
+    assert_eq!(get_bare_url_ranges("https://a.com/x"), vec![0..15]);
+    assert_eq!(
+      get_bare_url_ranges("(http://a.com), https://b.com."),
+      vec![1..13, 16..29]
+    );
+    assert!(get_bare_url_ranges("nothttps://a.com http https://").is_empty());
+  }
+
+  #[test]
+  fn reports_undefined_fragments() {
+    let file_text = concat!(
+      "# Intro\n\n",
+      "## Café\n\n",
+      "## Intro\n\n",
+      "<a name=\"legacy\"></a>\n\n",
+      "[a](#intro-1) [b](#caf%C3%A9) [c](#legacy) [d](#top) [e](#missing) [f][ref]\n\n",
+      "[ref]: #intro-2\n",
+    );
+    assert_eq!(
+      check(file_text),
+      vec![
+        (19..27, "duplicate-heading"),
+        (105..118, "undefined-fragment"),
+        (129..144, "undefined-fragment"),
+      ]
+    );
+  }
+
+  #[test]
+  fn gets_heading_slugs() {
+    let file_text = "\u{FEFF}# Some *Title*\n\n## Some Title {#custom}\n\nSome Title\n---\n";
+    let config = ConfigurationBuilder::new().heading_attributes(true).build();
+    let slugs = get_heading_slugs(file_text, &config).unwrap();
+    assert_eq!(
+      slugs,
+      vec![
+        HeadingSlug {
+          range: 3..17,
+          level: 1,
+          text: "Some Title".to_string(),
+          slug: "some-title".to_string(),
+        },
+        HeadingSlug {
+          range: 19..42,
+          level: 2,
+          text: "Some Title".to_string(),
+          slug: "custom".to_string(),
+        },
+        HeadingSlug {
+          range: 44..58,
+          level: 2,
+          text: "Some Title".to_string(),
+          slug: "some-title-1".to_string(),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_heading_level_changes() {
+    let config = ConfigurationBuilder::new()
+      .heading_levels(HeadingLevels::Normalize)
+      .heading_start_level(2)
+      .build();
+    let diagnostics = check_text("# A\n\n### B\n\n## C\n", &config).unwrap();
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|diagnostic| (diagnostic.range.clone(), diagnostic.rule, diagnostic.message.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (0..3, "heading-level", "Heading level 1 will be formatted as level 2."),
+        (
+          5..10,
+          "heading-increment",
+          "Heading level 3 should be at most 2 after a level 1 heading."
+        ),
+        (12..16, "heading-level", "Heading level 2 will be formatted as level 3."),
+      ]
+    );
+  }
+
+  #[test]
+  fn offsets_ranges_by_bom() {
+    let file_text = "\u{FEFF}[^a]\n";
+    let diagnostics = check_text(file_text, &ConfigurationBuilder::new().build()).unwrap();
+    assert_eq!(
+      diagnostics,
+      vec![Diagnostic {
+        range: 3..7,
+        rule: "undefined-footnote",
+        message: "Footnote [^a] has no definition.".to_string(),
+        severity: DiagnosticSeverity::Error,
+      }]
+    );
+  }
+}
@@ -72,6 +72,17 @@ impl ConfigurationBuilder {
   pub fn strong_kind(&mut self, value: StrongKind) -> &mut Self {
     self.insert("strongKind", value.to_string().into())
   }
+  /// The delimiter to use for strikethrough.
+  /// Default: `StrikethroughKind::Double`
+  pub fn strikethrough_kind(&mut self, value: StrikethroughKind) -> &mut Self {
+    self.insert("strikethroughKind", value.to_string().into())
+  }
+
+  /// The nesting order to use for text that is both strong and emphasized.
+  /// Default: `StrongEmphasisOrder::Preserve`
+  pub fn strong_emphasis_order(&mut self, value: StrongEmphasisOrder) -> &mut Self {
+    self.insert("strongEmphasisOrder", value.to_string().into())
+  }
 
   /// The character to use for lists.
   /// Default: `UnorderedListKind::Dashes`
@@ -79,12 +90,177 @@ impl ConfigurationBuilder {
     self.insert("unorderedListKind", value.to_string().into())
   }
 
+  /// Whether the items of lists are separated by blank lines.
+  /// Default: `ListSpacing::Preserve`
+  pub fn list_spacing(&mut self, value: ListSpacing) -> &mut Self {
+    self.insert("listSpacing", value.to_string().into())
+  }
+
+  /// The case of the `x` in checked task list markers.
+  /// Default: `TaskListMarkerCase::Lowercase`
+  pub fn task_list_marker_case(&mut self, value: TaskListMarkerCase) -> &mut Self {
+    self.insert("taskListMarkerCase", value.to_string().into())
+  }
+
+  /// Where the completed items of task lists go.
+  /// Default: `CompletedTaskOrder::Preserve`
+  pub fn completed_task_order(&mut self, value: CompletedTaskOrder) -> &mut Self {
+    self.insert("completedTaskOrder", value.to_string().into())
+  }
+
+  /// The character to use for unordered lists with task items.
+  /// Default: the value of `unordered_list_kind`
+  pub fn task_list_kind(&mut self, value: UnorderedListKind) -> &mut Self {
+    self.insert("taskListKind", value.to_string().into())
+  }
+
   /// The type of heading to use.
   /// Default: `HeadingKind::Atx`
   pub fn heading_kind(&mut self, value: HeadingKind) -> &mut Self {
     self.insert("headingKind", value.to_string().into())
   }
 
+  /// How backslash escapes in text are handled.
+  /// Default: `Escapes::Preserve`
+  pub fn escapes(&mut self, value: Escapes) -> &mut Self {
+    self.insert("escapes", value.to_string().into())
+  }
+
+  /// How character references (ex. `&copy;`) in text are handled.
+  /// Default: `CharacterReferences::Preserve`
+  pub fn character_references(&mut self, value: CharacterReferences) -> &mut Self {
+    self.insert("characterReferences", value.to_string().into())
+  }
+
+  /// How quotes, dashes, and ellipses in text are written.
+  /// Default: `Punctuation::Preserve`
+  pub fn punctuation(&mut self, value: Punctuation) -> &mut Self {
+    self.insert("punctuation", value.to_string().into())
+  }
+
+  /// The grammar used to recognize callouts in block quotes.
+  /// Default: `CalloutSyntax::Github`
+  pub fn callout_syntax(&mut self, value: CalloutSyntax) -> &mut Self {
+    self.insert("calloutSyntax", value.to_string().into())
+  }
+
+  /// How footnotes with numeric labels (ex. `[^1]`) are numbered.
+  /// Default: `FootnoteNumbering::Preserve`
+  pub fn footnote_numbering(&mut self, value: FootnoteNumbering) -> &mut Self {
+    self.insert("footnoteNumbering", value.to_string().into())
+  }
+
+  /// Where footnote definitions are placed in the file.
+  /// Default: `FootnoteDefinitionPosition::Preserve`
+  pub fn footnote_definition_position(&mut self, value: FootnoteDefinitionPosition) -> &mut Self {
+    self.insert("footnoteDefinitionPosition", value.to_string().into())
+  }
+
+  /// What to do with footnote and link reference definitions that are never referenced.
+  /// Default: `UnusedDefinitions::Preserve`
+  pub fn unused_definitions(&mut self, value: UnusedDefinitions) -> &mut Self {
+    self.insert("unusedDefinitions", value.to_string().into())
+  }
+
+  /// How heading anchors (ex. `#some-heading`) are generated for tables of contents
+  /// and when checking links to headings.
+  /// Default: `SlugStyle::Github`
+  pub fn slug_style(&mut self, value: SlugStyle) -> &mut Self {
+    self.insert("slugStyle", value.to_string().into())
+  }
+
+  /// How heading levels are formatted.
+  /// Default: `HeadingLevels::Preserve`
+  pub fn heading_levels(&mut self, value: HeadingLevels) -> &mut Self {
+    self.insert("headingLevels", value.to_string().into())
+  }
+
+  /// The level of the top headings when normalizing heading levels (1 to 6).
+  /// Default: `1`
+  pub fn heading_start_level(&mut self, value: u32) -> &mut Self {
+    self.insert("headingStartLevel", (value as i32).into())
+  }
+
+  /// The maximum number of consecutive blank lines kept between blocks.
+  /// Default: `1`
+  pub fn max_blank_lines(&mut self, value: u32) -> &mut Self {
+    self.insert("maxBlankLines", (value as i32).into())
+  }
+
+  /// The number of blank lines before headings (ex. `2` to separate sections more).
+  /// Default: `1`
+  pub fn blank_lines_before_headings(&mut self, value: u32) -> &mut Self {
+    self.insert("blankLinesBeforeHeadings", (value as i32).into())
+  }
+
+  /// The deepest heading level that `blank_lines_before_headings` applies to.
+  /// Default: `6`
+  pub fn blank_lines_before_headings_max_level(&mut self, value: u32) -> &mut Self {
+    self.insert("blankLinesBeforeHeadingsMaxLevel", (value as i32).into())
+  }
+
+  /// How blank lines between consecutive link reference definitions are formatted.
+  /// Default: `LinkReferenceSpacing::Tight`
+  pub fn link_reference_spacing(&mut self, value: LinkReferenceSpacing) -> &mut Self {
+    self.insert("linkReferenceSpacing", value.to_string().into())
+  }
+
+  /// The maximum number of consecutive blank lines kept in HTML blocks and between
+  /// the blocks inside an HTML element (ex. from `<div>` to `</div>`). At least one
+  /// blank line is kept between blocks and elements like `<pre>` whose blank lines
+  /// are content are kept as-is.
+  /// Default: all blank lines are kept
+  pub fn max_blank_lines_in_html(&mut self, value: u32) -> &mut Self {
+    self.insert("maxBlankLinesInHtml", (value as i32).into())
+  }
+
+  /// The case of the words in headings.
+  /// Default: `HeadingCase::Preserve`
+  pub fn heading_case(&mut self, value: HeadingCase) -> &mut Self {
+    self.insert("headingCase", value.to_string().into())
+  }
+
+  /// Words written as-is when changing the case of headings, such as proper nouns
+  /// and acronyms (ex. `GitHub`). Words with a capital letter after the first
+  /// letter are always written as-is.
+  /// Default: `[]`
+  pub fn heading_case_exceptions(&mut self, value: &[&str]) -> &mut Self {
+    let value = value.iter().map(|word| word.to_string().into()).collect();
+    self.insert("headingCaseExceptions", ConfigKeyValue::Array(value))
+  }
+
+  /// Callout kinds (ex. `> [!DANGER]`) allowed in addition to the GitHub alert
+  /// kinds of NOTE, TIP, IMPORTANT, WARNING, and CAUTION.
+  /// Default: `[]`
+  pub fn custom_callout_kinds(&mut self, value: &[&str]) -> &mut Self {
+    let value = value.iter().map(|kind| kind.to_string().into()).collect();
+    self.insert("customCalloutKinds", ConfigKeyValue::Array(value))
+  }
+
+  /// Whether to parse wiki links (ex. `[[Page|alias]]`) and embeds (ex. `![[image.png]]`).
+  /// Default: `false`
+  pub fn wiki_links(&mut self, value: bool) -> &mut Self {
+    self.insert("wikiLinks", value.into())
+  }
+
+  /// Whether to parse definition lists (ex. `Term\n: Definition`).
+  /// Default: `false`
+  pub fn definition_lists(&mut self, value: bool) -> &mut Self {
+    self.insert("definitionLists", value.into())
+  }
+
+  /// Whether to parse attribute blocks at the end of headings (ex. `# Title {#custom-id}`).
+  /// Default: `false`
+  pub fn heading_attributes(&mut self, value: bool) -> &mut Self {
+    self.insert("headingAttributes", value.into())
+  }
+
+  /// Whether to parse superscripts (ex. `^2^`) and subscripts (ex. `~2~`).
+  /// Default: `false`
+  pub fn subscript_superscript(&mut self, value: bool) -> &mut Self {
+    self.insert("subscriptSuperscript", value.into())
+  }
+
   /// The directive used to ignore a line.
   /// Default: `dprint-ignore`
   pub fn ignore_directive(&mut self, value: &str) -> &mut Self {
@@ -109,6 +285,18 @@ impl ConfigurationBuilder {
     self.insert("ignoreEndDirective", value.to_string().into())
   }
 
+  /// The directive used to mark the start of a generated table of contents.
+  /// Default: `toc`
+  pub fn toc_start_directive(&mut self, value: &str) -> &mut Self {
+    self.insert("tocStartDirective", value.to_string().into())
+  }
+
+  /// The directive used to mark the end of a generated table of contents.
+  /// Default: `tocstop`
+  pub fn toc_end_directive(&mut self, value: &str) -> &mut Self {
+    self.insert("tocEndDirective", value.to_string().into())
+  }
+
   pub fn deno(&mut self) -> &mut Self {
     self
       .text_wrap(TextWrap::Always)
@@ -145,15 +333,45 @@ mod tests {
       .text_wrap(TextWrap::Always)
       .emphasis_kind(EmphasisKind::Asterisks)
       .strong_kind(StrongKind::Underscores)
+      .strong_emphasis_order(StrongEmphasisOrder::StrongOuter)
+      .strikethrough_kind(StrikethroughKind::Single)
       .unordered_list_kind(UnorderedListKind::Asterisks)
+      .list_spacing(ListSpacing::Consistent)
+      .task_list_marker_case(TaskListMarkerCase::Uppercase)
+      .completed_task_order(CompletedTaskOrder::Last)
+      .task_list_kind(UnorderedListKind::Dashes)
       .heading_kind(HeadingKind::Atx)
+      .escapes(Escapes::Minimal)
+      .character_references(CharacterReferences::Literal)
+      .punctuation(Punctuation::Typographic)
+      .callout_syntax(CalloutSyntax::Obsidian)
+      .footnote_numbering(FootnoteNumbering::Sequential)
+      .footnote_definition_position(FootnoteDefinitionPosition::End)
+      .unused_definitions(UnusedDefinitions::Remove)
+      .slug_style(SlugStyle::Mkdocs)
+      .heading_levels(HeadingLevels::Normalize)
+      .heading_start_level(2)
+      .heading_case(HeadingCase::SentenceCase)
+      .heading_case_exceptions(&["Rust"])
+      .max_blank_lines(2)
+      .blank_lines_before_headings(2)
+      .blank_lines_before_headings_max_level(2)
+      .link_reference_spacing(LinkReferenceSpacing::Preserve)
+      .max_blank_lines_in_html(1)
+      .custom_callout_kinds(&["DANGER"])
+      .wiki_links(true)
+      .definition_lists(true)
+      .heading_attributes(true)
+      .subscript_superscript(true)
       .ignore_directive("test")
       .ignore_file_directive("test")
       .ignore_start_directive("test")
-      .ignore_end_directive("test");
+      .ignore_end_directive("test")
+      .toc_start_directive("test")
+      .toc_end_directive("test");
 
     let inner_config = config.get_inner_config();
-    assert_eq!(inner_config.len(), 11);
+    assert_eq!(inner_config.len(), 41);
     let diagnostics = resolve_config(inner_config, &Default::default()).diagnostics;
     assert_eq!(diagnostics.len(), 0);
   }
@@ -232,4 +450,18 @@ mod tests {
     assert_eq!(result.diagnostics[0].property_name, "tags");
     assert!(result.diagnostics[0].message.contains("Expected an object"));
   }
+
+  #[test]
+  fn custom_callout_kinds_invalid() {
+    let mut config = ConfigKeyMap::new();
+    config.insert(
+      "customCalloutKinds".into(),
+      ConfigKeyValue::Array(vec!["DANGER".into(), "NOT VALID".into()]),
+    );
+
+    let result = resolve_config(config, &Default::default());
+    assert_eq!(result.diagnostics.len(), 1);
+    assert_eq!(result.diagnostics[0].property_name, "customCalloutKinds");
+    assert_eq!(result.config.custom_callout_kinds, vec!["DANGER".to_string()]);
+  }
 }
@@ -39,6 +39,24 @@ pub fn resolve_config(
   }
 
   let tags = get_tags(&mut config, &mut diagnostics);
+  let custom_callout_kinds = get_custom_callout_kinds(&mut config, &mut diagnostics);
+  let heading_start_level = get_heading_start_level(&mut config, &mut diagnostics);
+  let heading_case_exceptions = get_heading_case_exceptions(&mut config, &mut diagnostics);
+  let task_list_kind = if config.contains_key("taskListKind") {
+    Some(get_value(
+      &mut config,
+      "taskListKind",
+      UnorderedListKind::Dashes,
+      &mut diagnostics,
+    ))
+  } else {
+    None
+  };
+  let max_blank_lines_in_html = if config.contains_key("maxBlankLinesInHtml") {
+    Some(get_value(&mut config, "maxBlankLinesInHtml", 0, &mut diagnostics))
+  } else {
+    None
+  };
 
   let resolved_config = Configuration {
     line_width: get_value(
@@ -58,13 +76,90 @@ pub fn resolve_config(
     text_wrap: get_value(&mut config, "textWrap", TextWrap::Maintain, &mut diagnostics),
     emphasis_kind: get_value(&mut config, "emphasisKind", EmphasisKind::Underscores, &mut diagnostics),
     strong_kind: get_value(&mut config, "strongKind", StrongKind::Asterisks, &mut diagnostics),
+    strikethrough_kind: get_value(
+      &mut config,
+      "strikethroughKind",
+      StrikethroughKind::Double,
+      &mut diagnostics,
+    ),
+    strong_emphasis_order: get_value(
+      &mut config,
+      "strongEmphasisOrder",
+      StrongEmphasisOrder::Preserve,
+      &mut diagnostics,
+    ),
     unordered_list_kind: get_value(
       &mut config,
       "unorderedListKind",
       UnorderedListKind::Dashes,
       &mut diagnostics,
     ),
+    list_spacing: get_value(&mut config, "listSpacing", ListSpacing::Preserve, &mut diagnostics),
+    task_list_marker_case: get_value(
+      &mut config,
+      "taskListMarkerCase",
+      TaskListMarkerCase::Lowercase,
+      &mut diagnostics,
+    ),
+    completed_task_order: get_value(
+      &mut config,
+      "completedTaskOrder",
+      CompletedTaskOrder::Preserve,
+      &mut diagnostics,
+    ),
+    task_list_kind,
     heading_kind: get_value(&mut config, "headingKind", HeadingKind::Atx, &mut diagnostics),
+    escapes: get_value(&mut config, "escapes", Escapes::Preserve, &mut diagnostics),
+    character_references: get_value(
+      &mut config,
+      "characterReferences",
+      CharacterReferences::Preserve,
+      &mut diagnostics,
+    ),
+    punctuation: get_value(&mut config, "punctuation", Punctuation::Preserve, &mut diagnostics),
+    callout_syntax: get_value(&mut config, "calloutSyntax", CalloutSyntax::Github, &mut diagnostics),
+    footnote_numbering: get_value(
+      &mut config,
+      "footnoteNumbering",
+      FootnoteNumbering::Preserve,
+      &mut diagnostics,
+    ),
+    footnote_definition_position: get_value(
+      &mut config,
+      "footnoteDefinitionPosition",
+      FootnoteDefinitionPosition::Preserve,
+      &mut diagnostics,
+    ),
+    unused_definitions: get_value(
+      &mut config,
+      "unusedDefinitions",
+      UnusedDefinitions::Preserve,
+      &mut diagnostics,
+    ),
+    slug_style: get_value(&mut config, "slugStyle", SlugStyle::Github, &mut diagnostics),
+    heading_levels: get_value(&mut config, "headingLevels", HeadingLevels::Preserve, &mut diagnostics),
+    heading_start_level,
+    heading_case: get_value(&mut config, "headingCase", HeadingCase::Preserve, &mut diagnostics),
+    heading_case_exceptions,
+    max_blank_lines: get_value(&mut config, "maxBlankLines", 1, &mut diagnostics),
+    blank_lines_before_headings: get_value(&mut config, "blankLinesBeforeHeadings", 1, &mut diagnostics),
+    blank_lines_before_headings_max_level: get_value(
+      &mut config,
+      "blankLinesBeforeHeadingsMaxLevel",
+      6,
+      &mut diagnostics,
+    ),
+    link_reference_spacing: get_value(
+      &mut config,
+      "linkReferenceSpacing",
+      LinkReferenceSpacing::Tight,
+      &mut diagnostics,
+    ),
+    max_blank_lines_in_html,
+    wiki_links: get_value(&mut config, "wikiLinks", false, &mut diagnostics),
+    definition_lists: get_value(&mut config, "definitionLists", false, &mut diagnostics),
+    heading_attributes: get_value(&mut config, "headingAttributes", false, &mut diagnostics),
+    subscript_superscript: get_value(&mut config, "subscriptSuperscript", false, &mut diagnostics),
     ignore_directive: get_value(
       &mut config,
       "ignoreDirective",
@@ -89,6 +184,9 @@ pub fn resolve_config(
       "dprint-ignore-end".to_string(),
       &mut diagnostics,
     ),
+    toc_start_directive: get_value(&mut config, "tocStartDirective", "toc".to_string(), &mut diagnostics),
+    toc_end_directive: get_value(&mut config, "tocEndDirective", "tocstop".to_string(), &mut diagnostics),
+    custom_callout_kinds,
     tags,
   };
 
@@ -147,6 +245,89 @@ fn get_tags(config: &mut ConfigKeyMap, diagnostics: &mut Vec<ConfigurationDiagno
   tags
 }
 
+fn get_custom_callout_kinds(config: &mut ConfigKeyMap, diagnostics: &mut Vec<ConfigurationDiagnostic>) -> Vec<String> {
+  let mut kinds = Vec::new();
+
+  if let Some(value) = config.shift_remove("customCalloutKinds") {
+    match value {
+      ConfigKeyValue::Array(values) => {
+        for value in values.into_iter() {
+          match value {
+            ConfigKeyValue::String(kind) if !kind.is_empty() && kind.chars().all(is_callout_kind_char) => {
+              kinds.push(kind)
+            }
+            _ => {
+              diagnostics.push(ConfigurationDiagnostic {
+                property_name: "customCalloutKinds".to_string(),
+                message: "Expected only letters, digits, hyphens, and underscores in each callout kind".to_string(),
+              });
+            }
+          }
+        }
+      }
+      _ => {
+        diagnostics.push(ConfigurationDiagnostic {
+          property_name: "customCalloutKinds".to_string(),
+          message: "Expected an array for 'customCalloutKinds' configuration".to_string(),
+        });
+      }
+    }
+  }
+
+  kinds
+}
+
+fn get_heading_case_exceptions(
+  config: &mut ConfigKeyMap,
+  diagnostics: &mut Vec<ConfigurationDiagnostic>,
+) -> Vec<String> {
+  let mut words = Vec::new();
+
+  if let Some(value) = config.shift_remove("headingCaseExceptions") {
+    match value {
+      ConfigKeyValue::Array(values) => {
+        for value in values.into_iter() {
+          match value {
+            ConfigKeyValue::String(word) if !word.is_empty() && !word.contains(char::is_whitespace) => words.push(word),
+            _ => {
+              diagnostics.push(ConfigurationDiagnostic {
+                property_name: "headingCaseExceptions".to_string(),
+                message: "Expected each heading case exception to be a single word".to_string(),
+              });
+            }
+          }
+        }
+      }
+      _ => {
+        diagnostics.push(ConfigurationDiagnostic {
+          property_name: "headingCaseExceptions".to_string(),
+          message: "Expected an array for 'headingCaseExceptions' configuration".to_string(),
+        });
+      }
+    }
+  }
+
+  words
+}
+
+fn get_heading_start_level(config: &mut ConfigKeyMap, diagnostics: &mut Vec<ConfigurationDiagnostic>) -> u32 {
+  let level = get_value(config, "headingStartLevel", 1, diagnostics);
+  if (1..=6).contains(&level) {
+    level
+  } else {
+    diagnostics.push(ConfigurationDiagnostic {
+      property_name: "headingStartLevel".to_string(),
+      message: "Expected a heading level from 1 to 6".to_string(),
+    });
+    1
+  }
+}
+
+/// Gets if the character is allowed in a callout kind (ex. `NOTE` in `[!NOTE]`).
+pub fn is_callout_kind_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
 fn fill_deno_config(config: &mut ConfigKeyMap) {
   for (key, value) in ConfigurationBuilder::new().deno().config.iter() {
     if !config.contains_key(key) {
@@ -14,12 +14,61 @@ pub struct Configuration {
   pub text_wrap: TextWrap,
   pub emphasis_kind: EmphasisKind,
   pub strong_kind: StrongKind,
+  pub strong_emphasis_order: StrongEmphasisOrder,
+  pub strikethrough_kind: StrikethroughKind,
   pub unordered_list_kind: UnorderedListKind,
+  pub list_spacing: ListSpacing,
+  pub task_list_marker_case: TaskListMarkerCase,
+  pub completed_task_order: CompletedTaskOrder,
+  /// The character to use for unordered lists with task items or `None` to use `unordered_list_kind`.
+  #[serde(default)]
+  pub task_list_kind: Option<UnorderedListKind>,
   pub heading_kind: HeadingKind,
+  pub escapes: Escapes,
+  pub character_references: CharacterReferences,
+  pub punctuation: Punctuation,
+  pub callout_syntax: CalloutSyntax,
+  pub footnote_numbering: FootnoteNumbering,
+  pub footnote_definition_position: FootnoteDefinitionPosition,
+  pub unused_definitions: UnusedDefinitions,
+  pub slug_style: SlugStyle,
+  pub heading_levels: HeadingLevels,
+  /// The level of the top headings when normalizing heading levels (1 to 6).
+  pub heading_start_level: u32,
+  pub heading_case: HeadingCase,
+  /// The maximum number of blank lines kept between blocks.
+  pub max_blank_lines: u32,
+  /// The number of blank lines before headings up to `blank_lines_before_headings_max_level`.
+  pub blank_lines_before_headings: u32,
+  pub blank_lines_before_headings_max_level: u32,
+  pub link_reference_spacing: LinkReferenceSpacing,
+  /// The maximum number of consecutive blank lines kept in HTML blocks and HTML elements or `None` to keep them all.
+  #[serde(default)]
+  pub max_blank_lines_in_html: Option<u32>,
+  /// Words written as-is when changing the case of headings (ex. `GitHub`).
+  #[serde(default)]
+  pub heading_case_exceptions: Vec<String>,
+  /// Callout kinds allowed in addition to the GitHub alert kinds.
+  #[serde(default)]
+  pub custom_callout_kinds: Vec<String>,
+  /// Whether to parse wiki links (ex. `[[Page|alias]]`) and embeds (ex. `![[image.png]]`).
+  #[serde(default)]
+  pub wiki_links: bool,
+  /// Whether to parse definition lists (ex. `Term\n: Definition`).
+  #[serde(default)]
+  pub definition_lists: bool,
+  /// Whether to parse attribute blocks at the end of headings (ex. `# Title {#custom-id}`).
+  #[serde(default)]
+  pub heading_attributes: bool,
+  /// Whether to parse superscripts (ex. `^2^`) and subscripts (ex. `~2~`).
+  #[serde(default)]
+  pub subscript_superscript: bool,
   pub ignore_directive: String,
   pub ignore_file_directive: String,
   pub ignore_start_directive: String,
   pub ignore_end_directive: String,
+  pub toc_start_directive: String,
+  pub toc_end_directive: String,
   /// Custom tag to file extension mappings for formatting code blocks.
   /// For example: { "custom-tag": "md" }
   #[serde(default)]
@@ -64,6 +113,38 @@ pub enum StrongKind {
 
 generate_str_to_from![StrongKind, [Asterisks, "asterisks"], [Underscores, "underscores"]];
 
+/// The delimiter to use for strikethrough.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StrikethroughKind {
+  /// Uses two tildes (~~) for strikethrough (default).
+  Double,
+  /// Uses a single tilde (~) for strikethrough. Two tildes are still used when
+  /// subscripts are parsed since a single tilde is a subscript.
+  Single,
+}
+
+generate_str_to_from![StrikethroughKind, [Double, "double"], [Single, "single"]];
+
+/// The nesting order to use for text that is both strong and emphasized.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StrongEmphasisOrder {
+  /// Keeps the nesting order used in the source.
+  Preserve,
+  /// Emphasis wraps the strong text (ex. `***text***` or `_**text**_`).
+  EmphasisOuter,
+  /// Strong wraps the emphasized text (ex. `**_text_**`).
+  StrongOuter,
+}
+
+generate_str_to_from![
+  StrongEmphasisOrder,
+  [Preserve, "preserve"],
+  [EmphasisOuter, "emphasisOuter"],
+  [StrongOuter, "strongOuter"]
+];
+
 /// The character to use primarily for lists.
 ///
 /// Unnumbered lists will be formatted to use a common list character, i.e., the primary list
@@ -99,6 +180,65 @@ impl UnorderedListKind {
 
 generate_str_to_from![UnorderedListKind, [Dashes, "dashes"], [Asterisks, "asterisks"]];
 
+/// Whether the items of a list are separated by blank lines.
+///
+/// This changes how lists are rendered because the items of a loose list are
+/// wrapped in paragraphs.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListSpacing {
+  /// Keeps the blank lines between items as written.
+  Preserve,
+  /// Removes the blank lines between items and before nested lists.
+  ///
+  /// Items with several paragraphs still make the list loose.
+  Tight,
+  /// Separates the items with a blank line.
+  Loose,
+  /// Makes each list tight or loose based on what most of its items use. Ties
+  /// use the spacing after the first item.
+  Consistent,
+}
+
+generate_str_to_from![
+  ListSpacing,
+  [Preserve, "preserve"],
+  [Tight, "tight"],
+  [Loose, "loose"],
+  [Consistent, "consistent"]
+];
+
+/// The case of the `x` in checked task list markers (ex. `- [x] Done`).
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskListMarkerCase {
+  /// Uses `[x]`.
+  Lowercase,
+  /// Uses `[X]`.
+  Uppercase,
+  /// Keeps the case as written.
+  Preserve,
+}
+
+generate_str_to_from![
+  TaskListMarkerCase,
+  [Lowercase, "lowercase"],
+  [Uppercase, "uppercase"],
+  [Preserve, "preserve"]
+];
+
+/// Where the completed items of task lists go.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CompletedTaskOrder {
+  /// Keeps the items in the order written.
+  Preserve,
+  /// Moves the checked items after the other items of the list, keeping their order.
+  Last,
+}
+
+generate_str_to_from![CompletedTaskOrder, [Preserve, "preserve"], [Last, "last"]];
+
 /// The style of heading to use for level 1 and level 2 headings:
 /// [setext](https://spec.commonmark.org/0.31.2/#setext-headings) or
 /// [ATX](https://spec.commonmark.org/0.31.2/#atx-headings). Level 3 and
@@ -115,3 +255,171 @@ pub enum HeadingKind {
 }
 
 generate_str_to_from![HeadingKind, [Setext, "setext"], [Atx, "atx"]];
+
+/// How backslash escapes in text are handled.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Escapes {
+  /// Keeps the escapes as they appear in the source.
+  Preserve,
+  /// Removes unnecessary escapes and adds the escapes that are required
+  /// for the text to keep its meaning (ex. a `-` that ends up at the start
+  /// of a line after wrapping).
+  Minimal,
+}
+
+generate_str_to_from![Escapes, [Preserve, "preserve"], [Minimal, "minimal"]];
+
+/// How character references (ex. `&copy;` or `&#169;`) in text are handled.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CharacterReferences {
+  /// Keeps the character references as they appear in the source.
+  Preserve,
+  /// Writes the character literally (ex. `&copy;` to `©`) when that doesn't
+  /// change the meaning of the text or make it hard to see. Otherwise, prefers
+  /// a named reference over a numeric one.
+  Literal,
+  /// Writes character references as named references (ex. `&#169;` to `&copy;`)
+  /// and writes common non-ASCII characters that have a name as a reference.
+  Named,
+}
+
+generate_str_to_from![
+  CharacterReferences,
+  [Preserve, "preserve"],
+  [Literal, "literal"],
+  [Named, "named"]
+];
+
+/// How quotes, dashes, and ellipses in text are written.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Punctuation {
+  /// Keeps the punctuation as it appears in the source.
+  Preserve,
+  /// Writes straight quotes, `--`, `---`, and `...` as typographic
+  /// characters (ex. `"a"` to `“a”` and `---` to `—`).
+  Typographic,
+  /// Writes typographic quotes, dashes, and ellipses as ASCII characters
+  /// (ex. `“a”` to `"a"` and `—` to `---`).
+  Ascii,
+}
+
+generate_str_to_from![
+  Punctuation,
+  [Preserve, "preserve"],
+  [Typographic, "typographic"],
+  [Ascii, "ascii"]
+];
+
+/// How footnotes with numeric labels (ex. `[^1]`) are numbered.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FootnoteNumbering {
+  /// Keeps the labels as written.
+  Preserve,
+  /// Renumbers the numeric labels starting at 1 in the order they're first referenced.
+  Sequential,
+}
+
+generate_str_to_from![FootnoteNumbering, [Preserve, "preserve"], [Sequential, "sequential"]];
+
+/// Where footnote definitions are placed in the file.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FootnoteDefinitionPosition {
+  /// Keeps the definitions where they're written.
+  Preserve,
+  /// Moves the definitions to the end of the file in the order they're first referenced.
+  End,
+}
+
+generate_str_to_from![FootnoteDefinitionPosition, [Preserve, "preserve"], [End, "end"]];
+
+/// What to do with footnote and link reference definitions that are never referenced.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnusedDefinitions {
+  /// Keeps the definitions.
+  Preserve,
+  /// Removes the definitions.
+  Remove,
+}
+
+generate_str_to_from![UnusedDefinitions, [Preserve, "preserve"], [Remove, "remove"]];
+
+/// How blank lines between consecutive link reference definitions are formatted.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkReferenceSpacing {
+  /// Removes the blank lines between the definitions.
+  Tight,
+  /// Keeps the blank lines between the definitions up to `max_blank_lines`.
+  Preserve,
+}
+
+generate_str_to_from![LinkReferenceSpacing, [Tight, "tight"], [Preserve, "preserve"]];
+
+/// How heading anchors (ex. `#some-heading`) are generated.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlugStyle {
+  /// Slugs like GitHub (ex. `what-is-it-1` for the second `What is it?`).
+  Github,
+  /// Slugs like GitLab, which also collapses consecutive hyphens.
+  Gitlab,
+  /// Slugs like MkDocs, which removes non-ASCII characters and uses an underscore
+  /// suffix for duplicates (ex. `what-is-it_1`).
+  Mkdocs,
+}
+
+generate_str_to_from![SlugStyle, [Github, "github"], [Gitlab, "gitlab"], [Mkdocs, "mkdocs"]];
+
+/// How heading levels are formatted.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HeadingLevels {
+  /// Keeps the levels as written.
+  Preserve,
+  /// Shifts the levels so the top headings are at the start level and removes
+  /// skipped levels (ex. a `###` directly under a `#` becomes `##`).
+  Normalize,
+}
+
+generate_str_to_from![HeadingLevels, [Preserve, "preserve"], [Normalize, "normalize"]];
+
+/// The case of the words in headings.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HeadingCase {
+  /// Keeps the case as written.
+  Preserve,
+  /// Capitalizes the first word of each sentence or clause after a `:` and lowercases
+  /// the rest (ex. `Getting started`).
+  SentenceCase,
+  /// Capitalizes each word except minor words like `and` or `of` that don't start a
+  /// sentence (ex. `Getting Started`).
+  TitleCase,
+}
+
+generate_str_to_from![
+  HeadingCase,
+  [Preserve, "preserve"],
+  [SentenceCase, "sentenceCase"],
+  [TitleCase, "titleCase"]
+];
+
+/// The grammar used to recognize callouts (ex. `> [!NOTE]`) in block quotes.
+#[derive(Clone, PartialEq, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CalloutSyntax {
+  /// Recognizes GitHub alert kinds and custom callout kinds, which are
+  /// written in uppercase.
+  Github,
+  /// Recognizes any kind along with a fold marker (ex. `> [!info]- Title`),
+  /// keeping the casing of the kind as written.
+  Obsidian,
+}
+
+generate_str_to_from![CalloutSyntax, [Github, "github"], [Obsidian, "obsidian"]];
@@ -0,0 +1,528 @@
+use anyhow::bail;
+use anyhow::Result;
+
+use super::configuration::Configuration;
+use super::format_text::format_text_with_code_block_tags;
+use super::generation::get_code_block_tag;
+
+/// Formats the markdown in the `///` and `//!` doc comments of a Rust file.
+///
+/// Each run of consecutive doc comment lines with the same prefix and indentation
+/// is formatted as a markdown document with a line width reduced by the width of
+/// the indentation and prefix. Code blocks that rustdoc treats as Rust, which includes
+/// code blocks without a tag (ex. `rust,no_run` or `ignore`), are provided to
+/// `format_code_block_text` with a `rust` tag unless they contain hidden lines.
+///
+/// Returns the file text or an error when a doc comment failed to parse.
+pub fn format_rust_doc_comments(
+  file_text: &str,
+  config: &Configuration,
+  mut format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  let new_line_text = if file_text.contains("\r\n") { "\r\n" } else { "\n" };
+  let lines = file_text.split('\n').collect::<Vec<_>>();
+  let mut result = String::with_capacity(file_text.len());
+  let mut i = 0;
+
+  while i < lines.len() {
+    let comment = match RustDocComment::parse(&lines[i..]) {
+      Some(comment) => comment,
+      None => {
+        result.push_str(lines[i]);
+        if i + 1 < lines.len() {
+          result.push('\n');
+        }
+        i += 1;
+        continue;
+      }
+    };
+    i += comment.line_count;
+
+    let prefix_width = get_text_width(comment.indent) + comment.prefix.len() + 1;
+    let formatted_text = format_doc_comment_text(
+      &comment.text,
+      config,
+      prefix_width,
+      get_rust_code_block_tag,
+      |tag, text, line_width| match tag {
+        "rust" if has_hidden_line(text) => Ok(None),
+        _ => format_code_block_text(tag, text, line_width),
+      },
+    )?;
+    let is_end_of_file = i == lines.len();
+    for (line_index, line) in formatted_text.lines().enumerate() {
+      if line_index > 0 {
+        result.push_str(new_line_text);
+      }
+      result.push_str(comment.indent);
+      result.push_str(comment.prefix);
+      if !line.is_empty() {
+        result.push(' ');
+        result.push_str(line);
+      }
+    }
+    if !is_end_of_file {
+      result.push_str(new_line_text);
+    }
+  }
+
+  if result == file_text {
+    Ok(None)
+  } else {
+    Ok(Some(result))
+  }
+}
+
+/// Formats the markdown description of a JSDoc or TSDoc comment block.
+///
+/// The `comment_text` is the full `/** ... */` comment and `indent_text` is the
+/// indentation of the line the comment starts on. Lines after the first `@tag`
+/// line are kept as-is other than their ` * ` prefix, which is only removed when
+/// all the lines after the first have one. Single line comments stay on a single
+/// line when the formatted description fits on one within the line width.
+///
+/// Returns the comment text or an error when it failed to parse.
+pub fn format_jsdoc_comment(
+  comment_text: &str,
+  indent_text: &str,
+  config: &Configuration,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  let inner_text = match comment_text
+    .strip_prefix("/**")
+    .and_then(|text| text.strip_suffix("*/"))
+  {
+    Some(inner_text) => inner_text,
+    None => bail!("Expected a comment block starting with /** and ending with */."),
+  };
+  let new_line_text = if comment_text.contains("\r\n") { "\r\n" } else { "\n" };
+  // only remove the leading `*` when every line has one so that text like `**bold**` stays as-is
+  let has_gutter = inner_text
+    .split('\n')
+    .skip(1)
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty())
+    .all(|line| line == "*" || line.starts_with("* ") || line.starts_with("*\t"));
+  let line_texts = inner_text
+    .split('\n')
+    .enumerate()
+    .map(|(i, line)| {
+      let line = line.trim_end();
+      if i > 0 && has_gutter {
+        let line = line.trim_start();
+        line.strip_prefix('*').unwrap_or(line)
+      } else {
+        line
+      }
+    })
+    .collect::<Vec<_>>();
+
+  // the description ends at the first tag line outside of a code block
+  let mut is_in_code_block = false;
+  let tags_index = line_texts
+    .iter()
+    .position(|line| {
+      let line = line.trim_start();
+      if line.starts_with("```") || line.starts_with("~~~") {
+        is_in_code_block = !is_in_code_block;
+      }
+      !is_in_code_block && line.starts_with('@')
+    })
+    .unwrap_or(line_texts.len());
+  let description = unindent_lines(&line_texts[..tags_index]);
+  if description.trim().is_empty() {
+    return Ok(None);
+  }
+
+  let prefix_width = get_text_width(indent_text) + 3;
+  let description = format_doc_comment_text(
+    &description,
+    config,
+    prefix_width,
+    get_code_block_tag,
+    format_code_block_text,
+  )?;
+  let description_lines = description.trim().lines().collect::<Vec<_>>();
+  let tag_lines = unindent_lines(&line_texts[tags_index..]);
+  let tag_lines = tag_lines.trim_end().lines().collect::<Vec<_>>();
+
+  let mut result = String::new();
+  let is_single_line = !comment_text.contains('\n')
+    && description_lines.len() == 1
+    && tag_lines.is_empty()
+    && prefix_width + 1 + description_lines[0].chars().count() + 3 <= config.line_width as usize;
+  if is_single_line {
+    result.push_str("/** ");
+    result.push_str(description_lines[0]);
+    result.push_str(" */");
+  } else {
+    result.push_str("/**");
+    let has_blank_line_before_tags =
+      !tag_lines.is_empty() && tags_index > 0 && line_texts[tags_index - 1].trim().is_empty();
+    let blank_line = if has_blank_line_before_tags { Some("") } else { None };
+    for line in description_lines
+      .iter()
+      .chain(blank_line.iter())
+      .chain(tag_lines.iter())
+    {
+      result.push_str(new_line_text);
+      result.push_str(indent_text);
+      result.push_str(" *");
+      if !line.is_empty() {
+        result.push(' ');
+        result.push_str(line);
+      }
+    }
+    result.push_str(new_line_text);
+    result.push_str(indent_text);
+    result.push_str(" */");
+  }
+
+  if result == comment_text {
+    Ok(None)
+  } else {
+    Ok(Some(result))
+  }
+}
+
+/// Formats markdown extracted from a comment where each line will be written
+/// after a prefix of the provided width.
+pub(crate) fn format_doc_comment_text(
+  text: &str,
+  config: &Configuration,
+  prefix_width: usize,
+  get_code_block_tag: fn(Option<&str>) -> Option<String>,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<String> {
+  if text.trim().is_empty() {
+    return Ok(text.to_string());
+  }
+
+  let mut config = config.clone();
+  config.line_width = std::cmp::max(10, config.line_width as i32 - prefix_width as i32) as u32;
+  Ok(
+    format_text_with_code_block_tags(text, &config, get_code_block_tag, format_code_block_text)?
+      .unwrap_or_else(|| text.to_string()),
+  )
+}
+
+/// Gets the width of text where a tab counts as four columns.
+pub(crate) fn get_text_width(text: &str) -> usize {
+  text.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+struct RustDocComment<'a> {
+  indent: &'a str,
+  prefix: &'static str,
+  line_count: usize,
+  text: String,
+}
+
+impl<'a> RustDocComment<'a> {
+  /// Parses the doc comment starting at the first line.
+  fn parse(lines: &[&'a str]) -> Option<Self> {
+    let (indent, prefix) = get_doc_comment_line_parts(lines[0])?;
+    let line_texts = lines
+      .iter()
+      .map(|line| line.trim_end_matches('\r'))
+      .map_while(|line| match get_doc_comment_line_parts(line) {
+        Some((line_indent, line_prefix)) if line_indent == indent && line_prefix == prefix => {
+          Some(&line[indent.len() + prefix.len()..])
+        }
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+
+    Some(RustDocComment {
+      indent,
+      prefix,
+      line_count: line_texts.len(),
+      // rustdoc removes the whitespace common to all the lines
+      text: unindent_lines(&line_texts),
+    })
+  }
+}
+
+/// Joins the lines with the whitespace common to all of them removed.
+fn unindent_lines(lines: &[&str]) -> String {
+  let common_indent = lines
+    .iter()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| line.len() - line.trim_start_matches(' ').len())
+    .min()
+    .unwrap_or(0);
+  let mut text = String::new();
+  for line in lines.iter() {
+    text.push_str(line.get(common_indent..).unwrap_or("").trim_end());
+    text.push('\n');
+  }
+  text
+}
+
+fn get_doc_comment_line_parts(line: &str) -> Option<(&str, &'static str)> {
+  let text = line.trim_start();
+  let indent = &line[..line.len() - text.len()];
+  if text.starts_with("///") && !text.starts_with("////") {
+    Some((indent, "///"))
+  } else if text.starts_with("//!") {
+    Some((indent, "//!"))
+  } else {
+    None
+  }
+}
+
+/// Gets the tag of a code block the way rustdoc does, where a code block is Rust
+/// code unless its info string has a token that isn't a rustdoc attribute
+/// (ex. `rust,no_run`, `ignore`, or no info string are Rust, but `text` isn't).
+fn get_rust_code_block_tag(info: Option<&str>) -> Option<String> {
+  let info = info.unwrap_or_default();
+  let mut tokens = info
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|token| !token.is_empty());
+  match tokens.find(|token| !is_rustdoc_attribute(token)) {
+    Some(tag) => Some(tag.to_string()),
+    None => Some("rust".to_string()),
+  }
+}
+
+fn is_rustdoc_attribute(token: &str) -> bool {
+  let is_number = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+  matches!(
+    token,
+    "rust" | "ignore" | "no_run" | "should_panic" | "compile_fail" | "test_harness" | "standalone_crate"
+  ) || token.starts_with("ignore-")
+    || token.strip_prefix("edition").is_some_and(is_number)
+    // error codes for compile_fail (ex. `E0382`)
+    || token.strip_prefix('E').is_some_and(is_number)
+}
+
+/// Gets if the code has lines that rustdoc hides (ex. `# use std::io;`).
+fn has_hidden_line(code: &str) -> bool {
+  code.lines().any(|line| {
+    let line = line.trim_start();
+    line == "#" || line.starts_with("# ")
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::configuration::ConfigurationBuilder;
+  use crate::configuration::TextWrap;
+
+  #[test]
+  fn formats_rust_doc_comments() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = concat!(
+      "//! Module   docs.\n",
+      "\n",
+      "/// Some  *text*.\n",
+      "///\n",
+      "/// *  item\n",
+      "fn test() {\n",
+      "  ///   Indented.\n",
+      "  //// Not a doc comment.\n",
+      "}\n",
+    );
+    let result = format_rust_doc_comments(input_text, &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(
+      result.unwrap(),
+      concat!(
+        "//! Module docs.\n",
+        "\n",
+        "/// Some _text_.\n",
+        "///\n",
+        "/// - item\n",
+        "fn test() {\n",
+        "  /// Indented.\n",
+        "  //// Not a doc comment.\n",
+        "}\n",
+      )
+    );
+  }
+
+  #[test]
+  fn uses_prefix_for_line_width() {
+    let config = ConfigurationBuilder::new()
+      .line_width(20)
+      .text_wrap(TextWrap::Always)
+      .build();
+    let input_text = "    /// Testing this out with some text\n";
+    let result = format_rust_doc_comments(input_text, &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(
+      result.unwrap(),
+      "    /// Testing this\n    /// out with\n    /// some text\n"
+    );
+  }
+
+  #[test]
+  fn formats_rust_code_blocks_without_hidden_lines() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = concat!(
+      "/// ```no_run\n",
+      "/// let a=1;\n",
+      "/// ```\n",
+      "///\n",
+      "/// ```rust\n",
+      "/// # let a=1;\n",
+      "/// let b=a;\n",
+      "/// ```\n",
+    );
+    let result = format_rust_doc_comments(input_text, &config, |tag, text, _| {
+      assert_eq!(tag, "rust");
+      Ok(Some(text.replace('=', " = ")))
+    })
+    .unwrap();
+    assert_eq!(
+      result.unwrap(),
+      concat!(
+        "/// ```no_run\n",
+        "/// let a = 1;\n",
+        "/// ```\n",
+        "///\n",
+        "/// ```rust\n",
+        "/// # let a=1;\n",
+        "/// let b=a;\n",
+        "/// ```\n",
+      )
+    );
+  }
+
+  #[test]
+  fn formats_untagged_and_attribute_code_blocks_as_rust() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = concat!(
+      "/// ```\n",
+      "/// let a=1;\n",
+      "/// ```\n",
+      "///\n",
+      "/// ```rust,no_run\n",
+      "/// let a=1;\n",
+      "/// ```\n",
+      "///\n",
+      "/// ```ignore,edition2021\n",
+      "/// # let a=1;\n",
+      "/// let b=a;\n",
+      "/// ```\n",
+      "///\n",
+      "/// ```compile_fail E0382\n",
+      "/// let a=1;\n",
+      "/// ```\n",
+      "///\n",
+      "/// ```\n",
+      "/// # let a=1;\n",
+      "/// ```\n",
+    );
+    let result = format_rust_doc_comments(input_text, &config, |tag, text, _| {
+      assert_eq!(tag, "rust");
+      Ok(Some(text.replace('=', " = ")))
+    })
+    .unwrap();
+    assert_eq!(
+      result.unwrap(),
+      concat!(
+        "/// ```\n",
+        "/// let a = 1;\n",
+        "/// ```\n",
+        "///\n",
+        "/// ```rust,no_run\n",
+        "/// let a = 1;\n",
+        "/// ```\n",
+        "///\n",
+        "/// ```ignore,edition2021\n",
+        "/// # let a=1;\n",
+        "/// let b=a;\n",
+        "/// ```\n",
+        "///\n",
+        "/// ```compile_fail E0382\n",
+        "/// let a = 1;\n",
+        "/// ```\n",
+        "///\n",
+        "/// ```\n",
+        "/// # let a=1;\n",
+        "/// ```\n",
+      )
+    );
+  }
+
+  #[test]
+  fn gets_rust_code_block_tags() {
+    assert_eq!(get_rust_code_block_tag(Some("text")), Some("text".to_string()));
+    assert_eq!(get_rust_code_block_tag(Some("ignore,json")), Some("json".to_string()));
+    assert_eq!(get_rust_code_block_tag(Some("should_panic")), Some("rust".to_string()));
+    assert_eq!(get_rust_code_block_tag(Some("ignore-wasm32")), Some("rust".to_string()));
+    assert_eq!(get_rust_code_block_tag(None), Some("rust".to_string()));
+  }
+
+  #[test]
+  fn keeps_crlf_newlines() {
+    let config = ConfigurationBuilder::new().build();
+    let result =
+      format_rust_doc_comments("/// *a*\r\n///\r\n/// b\r\nfn a() {}\r\n", &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(result.unwrap(), "/// _a_\r\n///\r\n/// b\r\nfn a() {}\r\n");
+  }
+
+  #[test]
+  fn handles_no_trailing_newline() {
+    let config = ConfigurationBuilder::new().build();
+    let result = format_rust_doc_comments("/// *a*\nfn a() {}", &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(result.unwrap(), "/// _a_\nfn a() {}");
+    let result = format_rust_doc_comments("fn a() {}\n/// *a*", &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(result.unwrap(), "fn a() {}\n/// _a_");
+    assert!(format_rust_doc_comments("/// _a_", &config, |_, _, _| Ok(None))
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn formats_jsdoc_comments() {
+    let config = ConfigurationBuilder::new().build();
+    let format = |text: &str| format_jsdoc_comment(text, "  ", &config, |_, _, _| Ok(None)).unwrap();
+
+    assert_eq!(format("/**  Some  *text*. */").unwrap(), "/** Some _text_. */");
+    assert_eq!(
+      format(concat!(
+        "/** Some  *text*.\n",
+        "   *\n",
+        "   *  *  item\n",
+        "   * ```ts\n",
+        "   * @decorator\n",
+        "   * ```\n",
+        "   *\n",
+        "   * @param a  -  *Opaque*.\n",
+        "   *   Continued.\n",
+        "   */",
+      ))
+      .unwrap(),
+      concat!(
+        "/**\n",
+        "   * Some _text_.\n",
+        "   *\n",
+        "   * - item\n",
+        "   *\n",
+        "   * ```ts\n",
+        "   * @decorator\n",
+        "   * ```\n",
+        "   *\n",
+        "   * @param a  -  *Opaque*.\n",
+        "   *   Continued.\n",
+        "   */",
+      )
+    );
+    assert!(format("/** @type {string} */").is_none());
+    assert!(format_jsdoc_comment("// a", "", &config, |_, _, _| Ok(None)).is_err());
+  }
+
+  #[test]
+  fn uses_prefix_for_jsdoc_line_width() {
+    let config = ConfigurationBuilder::new()
+      .line_width(20)
+      .text_wrap(TextWrap::Always)
+      .build();
+    let result = format_jsdoc_comment("/** Testing this out with text */", "    ", &config, |_, _, _| Ok(None));
+    assert_eq!(
+      result.unwrap().unwrap(),
+      "/**\n     * Testing this\n     * out with text\n     */"
+    );
+  }
+}
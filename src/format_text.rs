@@ -4,9 +4,28 @@ use dprint_core::configuration::resolve_new_line_kind;
 use dprint_core::formatting::*;
 
 use super::configuration::Configuration;
+use super::configuration::FootnoteDefinitionPosition;
+use super::configuration::FootnoteNumbering;
+use super::configuration::HeadingLevels;
+use super::configuration::UnusedDefinitions;
+use super::generation::apply_heading_case;
+use super::generation::collect_heading_slugs;
+use super::generation::common::Node;
+use super::generation::common::Range;
+use super::generation::common::Ranged;
+use super::generation::common::SourceFile;
 use super::generation::file_has_ignore_file_directive;
 use super::generation::generate;
+use super::generation::get_code_block_tag;
+use super::generation::get_ignore_comment_regex;
+use super::generation::get_leading_non_space_tab_byte_pos;
+use super::generation::get_table_of_contents_text;
+use super::generation::mask_mdx_syntax;
+use super::generation::move_footnote_definitions_to_end;
+use super::generation::normalize_heading_levels;
 use super::generation::parse_cmark_ast;
+use super::generation::remove_unused_definitions;
+use super::generation::renumber_footnotes;
 use super::generation::strip_metadata_header;
 use super::generation::Context;
 
@@ -18,7 +37,66 @@ pub fn format_text(
   config: &Configuration,
   format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
 ) -> Result<Option<String>> {
-  let result = format_text_inner(file_text, config, format_code_block_text)?;
+  format_text_with_kind(
+    file_text,
+    FileKind::Markdown,
+    config,
+    get_code_block_tag,
+    format_code_block_text,
+  )
+}
+
+/// Formats an MDX file.
+///
+/// ESM statements, JSX elements, and expressions are kept as-is while the
+/// surrounding markdown is formatted.
+///
+/// Returns the file text or an error when it failed to parse.
+pub fn format_mdx_text(
+  file_text: &str,
+  config: &Configuration,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  format_text_with_kind(
+    file_text,
+    FileKind::Mdx,
+    config,
+    get_code_block_tag,
+    format_code_block_text,
+  )
+}
+
+/// Formats a file using the provided function to get the tags of code blocks
+/// from their info strings.
+pub(crate) fn format_text_with_code_block_tags(
+  file_text: &str,
+  config: &Configuration,
+  get_code_block_tag: fn(Option<&str>) -> Option<String>,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  format_text_with_kind(
+    file_text,
+    FileKind::Markdown,
+    config,
+    get_code_block_tag,
+    format_code_block_text,
+  )
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum FileKind {
+  Markdown,
+  Mdx,
+}
+
+fn format_text_with_kind(
+  file_text: &str,
+  file_kind: FileKind,
+  config: &Configuration,
+  get_code_block_tag: fn(Option<&str>) -> Option<String>,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  let result = format_text_inner(file_text, file_kind, config, get_code_block_tag, format_code_block_text)?;
 
   match result {
     Some(result) if result == file_text => Ok(None),
@@ -29,18 +107,25 @@ pub fn format_text(
 
 fn format_text_inner(
   file_text: &str,
+  file_kind: FileKind,
   config: &Configuration,
+  get_code_block_tag: fn(Option<&str>) -> Option<String>,
   format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
 ) -> Result<Option<String>> {
   let file_text = strip_bom(file_text);
-  let (source_file, markdown_text) = match parse_source_file(file_text, config)? {
+  let toc_file_text = update_tables_of_contents(file_text, file_kind, config)?;
+  let file_text = toc_file_text.as_deref().unwrap_or(file_text);
+  let (mut source_file, markdown_text) = match parse_source_file(file_text, file_kind, config)? {
     ParseFileResult::IgnoreFile => return Ok(None),
     ParseFileResult::SourceFile(file) => file,
   };
+  update_definitions(&mut source_file, markdown_text, config);
+  normalize_headings(&mut source_file, markdown_text, config);
 
   Ok(Some(dprint_core::formatting::format(
     || {
       let mut context = Context::new(markdown_text, config, format_code_block_text);
+      context.get_code_block_tag = get_code_block_tag;
       #[allow(clippy::let_and_return)]
       let print_items = generate(&source_file.into(), &mut context);
       // eprintln!("{}", print_items.get_as_text());
@@ -50,13 +135,254 @@ fn format_text_inner(
   )))
 }
 
+/// Removes unused definitions and renumbers and moves the footnotes as configured,
+/// leaving ignored nodes as-is.
+fn update_definitions(source_file: &mut SourceFile, file_text: &str, config: &Configuration) {
+  let nodes = &mut source_file.children;
+  let should_remove = config.unused_definitions == UnusedDefinitions::Remove;
+  let should_renumber = config.footnote_numbering == FootnoteNumbering::Sequential;
+  let should_move = config.footnote_definition_position == FootnoteDefinitionPosition::End;
+  if !should_remove && !should_renumber && !should_move {
+    return;
+  }
+
+  let ignored_groups = get_ignored_node_groups(nodes, file_text, config);
+  let is_ignored = |index: usize| ignored_groups.iter().any(|group| group.contains(&index));
+  if should_remove {
+    remove_unused_definitions(nodes, file_text, is_ignored);
+  }
+  if should_renumber {
+    renumber_footnotes(nodes, is_ignored);
+  }
+  if should_move {
+    move_footnote_definitions_to_end(nodes, is_ignored);
+  }
+}
+
+/// Normalizes the levels and case of the headings as configured.
+fn normalize_headings(source_file: &mut SourceFile, file_text: &str, config: &Configuration) {
+  if config.heading_levels == HeadingLevels::Normalize {
+    let ignored_groups = get_ignored_node_groups(&source_file.children, file_text, config);
+    let is_ignored = |index: usize| ignored_groups.iter().any(|group| group.contains(&index));
+    normalize_heading_levels(&mut source_file.children, config.heading_start_level, is_ignored);
+  }
+  apply_heading_case(
+    &mut source_file.children,
+    config.heading_case,
+    &config.heading_case_exceptions,
+  );
+}
+
+/// Replaces the text between the table of contents directives (ex. `<!-- toc -->`
+/// and `<!-- tocstop -->`) with a nested list of links to the headings.
+///
+/// Returns the new file text or `None` when there's no table of contents.
+fn update_tables_of_contents(file_text: &str, file_kind: FileKind, config: &Configuration) -> Result<Option<String>> {
+  let (mut source_file, markdown_text) = match parse_source_file(file_text, file_kind, config)? {
+    ParseFileResult::IgnoreFile => return Ok(None),
+    ParseFileResult::SourceFile(file) => file,
+  };
+  let nodes = &source_file.children;
+  let start_regex = get_ignore_comment_regex(&config.toc_start_directive);
+  let end_regex = get_ignore_comment_regex(&config.toc_end_directive);
+  let html_matches = |node: &Node, regex: &regex::Regex| match node {
+    Node::Html(html) => regex.is_match(&markdown_text[html.range.clone()]),
+    _ => false,
+  };
+  let ignored_groups = get_ignored_node_groups(nodes, markdown_text, config);
+  let is_ignored = |index: usize| ignored_groups.iter().any(|group| group.contains(&index));
+
+  let mut toc_ranges = Vec::new();
+  let mut index = 0;
+  while index < nodes.len() {
+    if !is_ignored(index) && html_matches(&nodes[index], &start_regex) {
+      let end_index = nodes[index + 1..]
+        .iter()
+        .position(|node| html_matches(node, &end_regex))
+        .map(|position| index + 1 + position);
+      if let Some(end_index) = end_index {
+        toc_ranges.push(nodes[index].range().end..nodes[end_index].range().start);
+        index = end_index;
+      }
+    }
+    index += 1;
+  }
+  if toc_ranges.is_empty() {
+    return Ok(None);
+  }
+
+  // use the headings as they'll be formatted so the links match them
+  normalize_headings(&mut source_file, markdown_text, config);
+  let headings = collect_heading_slugs(&mut source_file.children, markdown_text, config.slug_style);
+  let toc_text = get_table_of_contents_text(&headings);
+  let mut new_text = String::with_capacity(markdown_text.len() + toc_text.len());
+  let mut last_end = 0;
+  for range in toc_ranges {
+    new_text.push_str(&markdown_text[last_end..range.start]);
+    new_text.push_str("\n\n");
+    new_text.push_str(&toc_text);
+    new_text.push('\n');
+    last_end = range.end;
+  }
+  new_text.push_str(&markdown_text[last_end..]);
+  Ok(Some(new_text))
+}
+
+/// Gets if formatting the file would change parts of it based on the whole document
+/// (ex. renumbering footnotes or regenerating a table of contents).
+fn has_document_changes(file_text: &str, config: &Configuration) -> Result<bool> {
+  if config.heading_levels == HeadingLevels::Normalize
+    || config.footnote_numbering == FootnoteNumbering::Sequential
+    || config.footnote_definition_position == FootnoteDefinitionPosition::End
+    || config.unused_definitions == UnusedDefinitions::Remove
+  {
+    return Ok(true);
+  }
+  let toc_file_text = update_tables_of_contents(file_text, FileKind::Markdown, config)?;
+  Ok(toc_file_text.is_some_and(|text| text != file_text))
+}
+
+/// Formats the top-level blocks of a file that intersect the provided byte range.
+///
+/// The range is expanded to the blocks it intersects along with any blocks that need
+/// to be formatted together with them (ex. consecutive lists or ignore comments) and
+/// only the text of those blocks is replaced.
+///
+/// Nothing is formatted when the configuration changes the whole document (ex. when
+/// normalizing heading levels) so that the result doesn't differ from formatting the file.
+///
+/// Returns the file text or an error when it failed to parse.
+pub fn format_text_range(
+  file_text: &str,
+  range: Range,
+  config: &Configuration,
+  format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
+) -> Result<Option<String>> {
+  let original_text = file_text;
+  let file_text = strip_bom(original_text);
+  let bom_len = original_text.len() - file_text.len();
+  let range = range.start.saturating_sub(bom_len)..range.end.saturating_sub(bom_len);
+  if has_document_changes(file_text, config)? {
+    return Ok(None);
+  }
+  let (mut source_file, markdown_text) = match parse_source_file(file_text, FileKind::Markdown, config)? {
+    ParseFileResult::IgnoreFile => return Ok(None),
+    ParseFileResult::SourceFile(file) => file,
+  };
+  let node_indexes = match get_range_node_indexes(&source_file.children, &range, file_text, config) {
+    Some(node_indexes) => node_indexes,
+    None => return Ok(None),
+  };
+  let mut children = source_file.children.drain(node_indexes).collect::<Vec<_>>();
+  apply_heading_case(&mut children, config.heading_case, &config.heading_case_exceptions);
+  let start = get_leading_non_space_tab_byte_pos(file_text, children.first().unwrap().range().start);
+  let end = children.last().unwrap().range().end;
+  let end = start + file_text[start..end].trim_end().len();
+  let source_file = SourceFile {
+    range: start..end,
+    children,
+  };
+
+  let formatted_text = dprint_core::formatting::format(
+    || {
+      let mut context = Context::new(markdown_text, config, format_code_block_text);
+      generate(&source_file.into(), &mut context)
+    },
+    config_to_print_options(file_text, config),
+  );
+  let result = format!(
+    "{}{}{}",
+    &file_text[..start],
+    formatted_text.trim_end_matches(['\r', '\n']),
+    &file_text[end..]
+  );
+
+  if result == original_text {
+    Ok(None)
+  } else {
+    Ok(Some(result))
+  }
+}
+
+/// Gets the indexes of the top-level nodes to format for the provided range.
+fn get_range_node_indexes(nodes: &[Node], range: &Range, file_text: &str, config: &Configuration) -> Option<Range> {
+  let intersects = |node_range: &Range| {
+    if range.is_empty() {
+      node_range.start <= range.start && range.start <= node_range.end
+    } else {
+      node_range.start < range.end && range.start < node_range.end
+    }
+  };
+  let mut start = nodes.iter().position(|node| intersects(node.range()))?;
+  let mut end = nodes.iter().rposition(|node| intersects(node.range()))? + 1;
+
+  // expand until all the nodes that depend on each other are included
+  let groups = get_node_groups(nodes, file_text, config);
+  loop {
+    let (last_start, last_end) = (start, end);
+    for group in groups.iter() {
+      if group.start < end && start < group.end {
+        start = std::cmp::min(start, group.start);
+        end = std::cmp::max(end, group.end);
+      }
+    }
+    if (start, end) == (last_start, last_end) {
+      break;
+    }
+  }
+
+  Some(start..end)
+}
+
+/// Gets the ranges of top-level nodes that are ignored along with their ignore comments.
+pub(crate) fn get_ignored_node_groups(nodes: &[Node], file_text: &str, config: &Configuration) -> Vec<Range> {
+  get_node_groups(nodes, file_text, config)
+    .into_iter()
+    .filter(|group| !matches!(nodes[group.start], Node::List(_)))
+    .collect()
+}
+
+/// Gets the ranges of top-level nodes whose formatting depends on each other.
+fn get_node_groups(nodes: &[Node], file_text: &str, config: &Configuration) -> Vec<Range> {
+  let ignore_regex = get_ignore_comment_regex(&config.ignore_directive);
+  let ignore_start_regex = get_ignore_comment_regex(&config.ignore_start_directive);
+  let ignore_end_regex = get_ignore_comment_regex(&config.ignore_end_directive);
+  let html_matches = |node: &Node, regex: &regex::Regex| match node {
+    Node::Html(html) => regex.is_match(&file_text[html.range.clone()]),
+    _ => false,
+  };
+
+  let mut groups = Vec::new();
+  for (i, node) in nodes.iter().enumerate() {
+    if html_matches(node, &ignore_regex) {
+      // the ignore comment and the node it ignores
+      groups.push(i..std::cmp::min(i + 2, nodes.len()));
+    } else if html_matches(node, &ignore_start_regex) {
+      let end = nodes[i + 1..]
+        .iter()
+        .position(|node| html_matches(node, &ignore_end_regex))
+        .map(|index| i + index + 2)
+        .unwrap_or(nodes.len());
+      groups.push(i..end);
+    } else if matches!(node, Node::List(_)) && (i == 0 || !matches!(nodes[i - 1], Node::List(_))) {
+      // consecutive lists alternate their markers
+      let count = nodes[i..]
+        .iter()
+        .take_while(|node| matches!(node, Node::List(_)))
+        .count();
+      groups.push(i..i + count);
+    }
+  }
+  groups
+}
+
 #[cfg(feature = "tracing")]
 pub fn trace_file(
   file_text: &str,
   config: &Configuration,
   format_code_block_text: impl for<'a> FnMut(&str, &'a str, u32) -> Result<Option<String>>,
 ) -> dprint_core::formatting::TracingResult {
-  let (source_file, markdown_text) = match parse_source_file(file_text, config).unwrap() {
+  let (source_file, markdown_text) = match parse_source_file(file_text, FileKind::Markdown, config).unwrap() {
     ParseFileResult::IgnoreFile => panic!("Cannot trace file because it has an ignore file comment."),
     ParseFileResult::SourceFile(file) => file,
   };
@@ -71,22 +397,31 @@ pub fn trace_file(
   )
 }
 
-fn strip_bom(text: &str) -> &str {
+pub(crate) fn strip_bom(text: &str) -> &str {
   text.strip_prefix("\u{FEFF}").unwrap_or(text)
 }
 
-enum ParseFileResult<'a> {
+pub(crate) enum ParseFileResult<'a> {
   IgnoreFile,
   SourceFile((crate::generation::common::SourceFile, &'a str)),
 }
 
-fn parse_source_file<'a>(file_text: &'a str, config: &Configuration) -> Result<ParseFileResult<'a>> {
+pub(crate) fn parse_source_file<'a>(
+  file_text: &'a str,
+  file_kind: FileKind,
+  config: &Configuration,
+) -> Result<ParseFileResult<'a>> {
   // check for the presence of a dprint-ignore-file comment before parsing
   if file_has_ignore_file_directive(strip_metadata_header(file_text), &config.ignore_file_directive) {
     return Ok(ParseFileResult::IgnoreFile);
   }
 
-  match parse_cmark_ast(file_text) {
+  // the masked text has the same length, so the node ranges apply to the file text
+  let parse_result = match file_kind {
+    FileKind::Markdown => parse_cmark_ast(file_text, config),
+    FileKind::Mdx => parse_cmark_ast(&mask_mdx_syntax(file_text), config),
+  };
+  match parse_result {
     Ok(source_file) => Ok(ParseFileResult::SourceFile((source_file, file_text))),
     Err(error) => bail!(
       "{}",
@@ -121,4 +456,175 @@ mod test {
       assert_eq!(result, Some("# Title\n".to_string()));
     }
   }
+
+  #[test]
+  fn formats_range() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = "#  Title\n\nSome   *text*.\n\n\n\nOther   *text*.\n";
+    let format = |range: Range| format_text_range(input_text, range, &config, |_, _, _| Ok(None)).unwrap();
+
+    assert_eq!(
+      format(12..14),
+      Some("#  Title\n\nSome _text_.\n\n\n\nOther   *text*.\n".to_string())
+    );
+    assert_eq!(
+      format(0..input_text.len()),
+      Some("# Title\n\nSome _text_.\n\nOther _text_.\n".to_string())
+    );
+    // between blocks
+    assert_eq!(format(26..26), None);
+  }
+
+  #[test]
+  fn formats_range_in_part_of_blocks() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = "#  Title\n\nSome   *text*\nwith   lines.\n\n-  a\n-  b\n\nOther   *text*.\n";
+    let format = |range: Range| format_text_range(input_text, range, &config, |_, _, _| Ok(None)).unwrap();
+
+    // starts in the middle of a paragraph
+    assert_eq!(
+      format(28..30),
+      Some("#  Title\n\nSome _text_\nwith lines.\n\n-  a\n-  b\n\nOther   *text*.\n".to_string())
+    );
+    // spans the end of a paragraph and the start of a list
+    assert_eq!(
+      format(28..42),
+      Some("#  Title\n\nSome _text_\nwith lines.\n\n- a\n- b\n\nOther   *text*.\n".to_string())
+    );
+  }
+
+  #[test]
+  fn formats_range_with_frontmatter() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = "---\ntitle:   x\n---\n\n#  A\n";
+    let format = |range: Range| format_text_range(input_text, range, &config, |_, _, _| Ok(None)).unwrap();
+
+    assert_eq!(format(0..3), None);
+    assert_eq!(format(8..10), None);
+    assert_eq!(format(22..23), Some("---\ntitle:   x\n---\n\n# A\n".to_string()));
+    assert_eq!(
+      format(0..input_text.len()),
+      Some("---\ntitle:   x\n---\n\n# A\n".to_string())
+    );
+    assert_eq!(
+      format_text_range(input_text, 0..3, &config, |tag, _, _| Ok(
+        (tag == "yaml").then(|| "title: x\n".to_string())
+      ))
+      .unwrap(),
+      Some("---\ntitle: x\n---\n\n#  A\n".to_string())
+    );
+  }
+
+  #[test]
+  fn skips_range_when_formatting_changes_whole_document() {
+    let format = |input_text: &str, config: &Configuration| {
+      format_text_range(input_text, 0..input_text.len(), config, |_, _, _| Ok(None)).unwrap()
+    };
+
+    let config = ConfigurationBuilder::new()
+      .heading_levels(HeadingLevels::Normalize)
+      .build();
+    assert_eq!(format("##  A\n", &config), None);
+    let config = ConfigurationBuilder::new()
+      .footnote_numbering(FootnoteNumbering::Sequential)
+      .build();
+    assert_eq!(format("Text[^b].\n\n[^b]:  B\n", &config), None);
+    let config = ConfigurationBuilder::new()
+      .footnote_definition_position(FootnoteDefinitionPosition::End)
+      .build();
+    assert_eq!(format("Text[^b].\n\n[^b]:  B\n", &config), None);
+    let config = ConfigurationBuilder::new()
+      .unused_definitions(UnusedDefinitions::Remove)
+      .build();
+    assert_eq!(format("#  A\n\n[a]: https://dprint.dev\n", &config), None);
+
+    let config = ConfigurationBuilder::new().build();
+    assert_eq!(format("<!-- toc -->\n<!-- tocstop -->\n\n#  A\n", &config), None);
+    assert_eq!(
+      format("<!-- toc -->\n\n- [A](#a)\n\n<!-- tocstop -->\n\n#  A\n", &config),
+      Some("<!-- toc -->\n\n- [A](#a)\n\n<!-- tocstop -->\n\n# A\n".to_string())
+    );
+  }
+
+  #[test]
+  fn formats_range_expanded_to_dependent_blocks() {
+    let config = ConfigurationBuilder::new().build();
+    let format =
+      |input_text: &str, range: Range| format_text_range(input_text, range, &config, |_, _, _| Ok(None)).unwrap();
+
+    assert_eq!(format("*  a\n\n-  b\n", 8..9), Some("- a\n\n* b\n".to_string()));
+    assert_eq!(format("<!-- dprint-ignore -->\n*  a\n", 25..26), None);
+    assert_eq!(
+      format(
+        "<!-- dprint-ignore-start -->\n*  a\n\n<!-- dprint-ignore-end -->\n",
+        32..33
+      ),
+      None
+    );
+  }
+
+  #[test]
+  fn formats_mdx() {
+    let config = ConfigurationBuilder::new().build();
+    let input_text = concat!(
+      "import Tabs from '@theme/Tabs';\n",
+      "export const meta = {\n",
+      "  a: 1,\n",
+      "};\n",
+      "\n",
+      "#  Title {frontMatter.title}\n",
+      "<Tabs\n",
+      "  values={[{ label: 'A', value: 'a' }]}>\n",
+      "\n",
+      "Some  *text* with {a*b*c} and <Badge count={1 > 0} />.\n",
+      "\n",
+      "</Tabs>\n",
+    );
+    let result = format_mdx_text(input_text, &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(
+      result.unwrap(),
+      concat!(
+        "import Tabs from '@theme/Tabs';\n",
+        "export const meta = {\n",
+        "  a: 1,\n",
+        "};\n",
+        "\n",
+        "# Title {frontMatter.title}\n",
+        "\n",
+        "<Tabs\n",
+        "  values={[{ label: 'A', value: 'a' }]}>\n",
+        "\n",
+        "Some _text_ with {a*b*c} and <Badge count={1 > 0} />.\n",
+        "\n",
+        "</Tabs>\n",
+      )
+    );
+  }
+
+  #[test]
+  fn formats_custom_callout_kinds() {
+    let config = ConfigurationBuilder::new().custom_callout_kinds(&["DANGER"]).build();
+    let input_text = "> [!danger]\n> Some danger\n\n> [!unknown]\n> Some text\n";
+    let result = format_text(input_text, &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(
+      result.unwrap(),
+      "> [!DANGER]\n> Some danger\n\n> [!unknown]\n> Some text\n"
+    );
+  }
+
+  #[test]
+  fn formats_heading_case_exceptions() {
+    let config = ConfigurationBuilder::new()
+      .heading_case(crate::configuration::HeadingCase::SentenceCase)
+      .heading_case_exceptions(&["Rust", "Markdown"])
+      .build();
+    let input_text = "# Using Rust With Markdown Files
+";
+    let result = format_text(input_text, &config, |_, _, _| Ok(None)).unwrap();
+    assert_eq!(
+      result.unwrap(),
+      "# Using Rust with Markdown files
+"
+    );
+  }
 }
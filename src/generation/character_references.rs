@@ -0,0 +1,198 @@
+use std::borrow::Cow;
+
+use super::common::CharacterReference;
+use crate::configuration::CharacterReferences;
+
+/// Named character references used when writing a character as a reference.
+///
+/// This is not every named reference, but the ones for characters that are
+/// commonly written as a reference.
+const NAMED_REFERENCES: &[(char, &str)] = &[
+  ('"', "quot"),
+  ('&', "amp"),
+  ('\'', "apos"),
+  ('<', "lt"),
+  ('>', "gt"),
+  ('\u{a0}', "nbsp"),
+  ('¡', "iexcl"),
+  ('¢', "cent"),
+  ('£', "pound"),
+  ('¤', "curren"),
+  ('¥', "yen"),
+  ('¦', "brvbar"),
+  ('§', "sect"),
+  ('¨', "uml"),
+  ('©', "copy"),
+  ('ª', "ordf"),
+  ('«', "laquo"),
+  ('¬', "not"),
+  ('\u{ad}', "shy"),
+  ('®', "reg"),
+  ('¯', "macr"),
+  ('°', "deg"),
+  ('±', "plusmn"),
+  ('²', "sup2"),
+  ('³', "sup3"),
+  ('´', "acute"),
+  ('µ', "micro"),
+  ('¶', "para"),
+  ('·', "middot"),
+  ('¸', "cedil"),
+  ('¹', "sup1"),
+  ('º', "ordm"),
+  ('»', "raquo"),
+  ('¼', "frac14"),
+  ('½', "frac12"),
+  ('¾', "frac34"),
+  ('¿', "iquest"),
+  ('×', "times"),
+  ('÷', "divide"),
+  ('\u{2002}', "ensp"),
+  ('\u{2003}', "emsp"),
+  ('\u{2009}', "thinsp"),
+  ('\u{200c}', "zwnj"),
+  ('\u{200d}', "zwj"),
+  ('\u{200e}', "lrm"),
+  ('\u{200f}', "rlm"),
+  ('–', "ndash"),
+  ('—', "mdash"),
+  ('‘', "lsquo"),
+  ('’', "rsquo"),
+  ('‚', "sbquo"),
+  ('“', "ldquo"),
+  ('”', "rdquo"),
+  ('„', "bdquo"),
+  ('†', "dagger"),
+  ('‡', "Dagger"),
+  ('•', "bull"),
+  ('…', "hellip"),
+  ('‰', "permil"),
+  ('′', "prime"),
+  ('″', "Prime"),
+  ('‹', "lsaquo"),
+  ('›', "rsaquo"),
+  ('€', "euro"),
+  ('™', "trade"),
+  ('←', "larr"),
+  ('↑', "uarr"),
+  ('→', "rarr"),
+  ('↓', "darr"),
+  ('↔', "harr"),
+  ('⇒', "rArr"),
+  ('⇔', "hArr"),
+  ('∀', "forall"),
+  ('−', "minus"),
+  ('∞', "infin"),
+  ('≈', "asymp"),
+  ('≠', "ne"),
+  ('≡', "equiv"),
+  ('≤', "le"),
+  ('≥', "ge"),
+];
+
+/// Gets the text to write for a character reference based on the configuration.
+pub fn get_character_reference_text<'a>(
+  reference: &'a CharacterReference,
+  raw_text: &'a str,
+  kind: CharacterReferences,
+) -> Cow<'a, str> {
+  match kind {
+    CharacterReferences::Preserve => Cow::Borrowed(raw_text),
+    CharacterReferences::Literal if is_safe_literal(&reference.value) => Cow::Borrowed(&reference.value),
+    CharacterReferences::Literal | CharacterReferences::Named => match get_named_reference(&reference.value) {
+      Some(named_reference) => Cow::Owned(named_reference),
+      None => Cow::Borrowed(raw_text),
+    },
+  }
+}
+
+/// Writes the characters in the text that have a name as a named reference
+/// when configured to do so.
+pub fn push_literal_text(result: &mut String, text: &str, kind: CharacterReferences) {
+  if kind != CharacterReferences::Named {
+    result.push_str(text);
+    return;
+  }
+
+  let mut last_char = None;
+  for c in text.chars() {
+    // a reference after a backslash would be escaped
+    match get_named_reference_for_char(c) {
+      Some(name) if !c.is_ascii() && last_char != Some('\\') => {
+        result.push('&');
+        result.push_str(name);
+        result.push(';');
+      }
+      _ => result.push(c),
+    }
+    last_char = Some(c);
+  }
+}
+
+fn get_named_reference(value: &str) -> Option<String> {
+  let mut chars = value.chars();
+  let c = chars.next()?;
+  if chars.next().is_some() {
+    return None;
+  }
+  get_named_reference_for_char(c).map(|name| format!("&{};", name))
+}
+
+fn get_named_reference_for_char(c: char) -> Option<&'static str> {
+  NAMED_REFERENCES
+    .iter()
+    .find(|(value, _)| *value == c)
+    .map(|(_, name)| *name)
+}
+
+/// Gets if the value can be written literally without changing the meaning
+/// of the text or becoming hard to see.
+fn is_safe_literal(value: &str) -> bool {
+  value.chars().all(|c| {
+    !c.is_ascii()
+      && !c.is_whitespace()
+      && !c.is_control()
+      && !matches!(
+        c,
+        '\u{ad}'
+          | '\u{300}'..='\u{36f}'
+          | '\u{61c}'
+          | '\u{115f}'..='\u{1160}'
+          | '\u{17b4}'..='\u{17b5}'
+          | '\u{180b}'..='\u{180f}'
+          | '\u{200b}'..='\u{200f}'
+          | '\u{2028}'..='\u{202e}'
+          | '\u{2060}'..='\u{206f}'
+          | '\u{3164}'
+          | '\u{fe00}'..='\u{fe0f}'
+          | '\u{feff}'
+          | '\u{ffa0}'
+          | '\u{fff0}'..='\u{fffd}'
+          | '\u{e0000}'..='\u{e007f}'
+      )
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn it_should_get_if_safe_literal() {
+    assert!(is_safe_literal("©"));
+    assert!(is_safe_literal("—"));
+    assert!(!is_safe_literal("<"));
+    assert!(!is_safe_literal("A"));
+    assert!(!is_safe_literal("\u{a0}"));
+    assert!(!is_safe_literal("\u{200b}"));
+    assert!(!is_safe_literal("\u{fffd}"));
+  }
+
+  #[test]
+  fn it_should_get_named_reference() {
+    assert_eq!(get_named_reference("©"), Some("&copy;".to_string()));
+    assert_eq!(get_named_reference("<"), Some("&lt;".to_string()));
+    assert_eq!(get_named_reference("a"), None);
+    assert_eq!(get_named_reference("©©"), None);
+  }
+}
@@ -1,15 +1,23 @@
 use super::parsing::parse_image as parse_image_from_text;
 use super::parsing::parse_link_reference;
 use super::parsing::parse_link_reference_definitions;
+use crate::configuration::is_callout_kind_char;
+use crate::configuration::CalloutSyntax;
+use crate::configuration::Configuration;
+use crate::configuration::Punctuation;
 use crate::generation::common::*;
 use crate::generation::trim_document_whitespace;
 use crate::generation::trim_spaces_and_newlines;
 use crate::generation::trim_start_spaces_and_newlines;
 use pulldown_cmark::*;
 
+/// The kinds of GitHub alerts.
+const GITHUB_CALLOUT_KINDS: [&str; 5] = ["NOTE", "TIP", "IMPORTANT", "WARNING", "CAUTION"];
+
 struct EventIterator<'a> {
   iterator: OffsetIter<'a, DefaultBrokenLinkCallback>,
   file_text: &'a str,
+  config: &'a Configuration,
   last_range: Range,
   next: Option<(Event<'a>, Range)>,
   allow_empty_text_events: bool,
@@ -17,12 +25,17 @@ struct EventIterator<'a> {
 }
 
 impl<'a> EventIterator<'a> {
-  pub fn new(file_text: &'a str, iterator: OffsetIter<'a, DefaultBrokenLinkCallback>) -> EventIterator<'a> {
+  pub fn new(
+    file_text: &'a str,
+    config: &'a Configuration,
+    iterator: OffsetIter<'a, DefaultBrokenLinkCallback>,
+  ) -> EventIterator<'a> {
     let mut iterator = iterator;
     let next = iterator.next();
     // eprintln!("Raw event: {:?}", next);
     EventIterator {
       file_text,
+      config,
       iterator,
       last_range: Range { start: 0, end: 0 },
       next,
@@ -99,20 +112,44 @@ impl<'a> EventIterator<'a> {
   }
 }
 
-pub fn parse_cmark_ast(markdown_text: &str) -> Result<SourceFile, ParseError> {
-  let mut options = Options::empty();
-  options.insert(Options::ENABLE_TABLES);
-  options.insert(Options::ENABLE_FOOTNOTES);
-  options.insert(Options::ENABLE_STRIKETHROUGH);
-  options.insert(Options::ENABLE_TASKLISTS);
-  options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
-  options.insert(Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
-  options.insert(Options::ENABLE_MATH);
+pub fn parse_cmark_ast(markdown_text: &str, config: &Configuration) -> Result<SourceFile, ParseError> {
+  if !config.definition_lists {
+    return parse_source_file(markdown_text, markdown_text, config);
+  }
 
+  // Pulldown cmark doesn't support definition lists, so the `:` of each definition
+  // is replaced with a `+` in order to parse its content as a list item with nested
+  // blocks. Definitions nested in those are then found when parsing the paragraphs.
+  let mut source_file = parse_source_file(markdown_text, markdown_text, config)?;
+  let mut markers = get_definition_markers(&mut source_file.children, markdown_text);
+  if markers.is_empty() {
+    return Ok(source_file);
+  }
+  markers.sort_unstable();
+  markers.dedup();
+  let mut parse_text = markdown_text.to_string();
+  for &marker in &markers {
+    parse_text.replace_range(marker..marker + 1, "+");
+  }
+  let mut definitions_source_file = parse_source_file(markdown_text, &parse_text, config)?;
+  let mut converted_markers = convert_definition_lists(&mut definitions_source_file.children, &markers, markdown_text);
+  converted_markers.sort_unstable();
+  if converted_markers == markers {
+    Ok(definitions_source_file)
+  } else {
+    // a marker that didn't end up as a definition changed the document structure
+    Ok(source_file)
+  }
+}
+
+/// Parses the source file where the text to parse only differs from the markdown text
+/// in characters that were replaced with characters of the same length.
+fn parse_source_file(markdown_text: &str, parse_text: &str, config: &Configuration) -> Result<SourceFile, ParseError> {
   let mut children: Vec<Node> = Vec::new();
   let mut iterator = EventIterator::new(
     markdown_text,
-    Parser::new_ext(markdown_text, options).into_offset_iter(),
+    config,
+    Parser::new_ext(parse_text, get_options(config)).into_offset_iter(),
   );
   let mut last_event_range: Option<Range> = None;
 
@@ -148,6 +185,42 @@ pub fn parse_cmark_ast(markdown_text: &str) -> Result<SourceFile, ParseError> {
   })
 }
 
+/// Gets the full (ex. `[text][label]`) and collapsed (ex. `[label][]`) link and image
+/// references that don't have a definition along with their labels.
+pub fn get_undefined_link_references(markdown_text: &str, config: &Configuration) -> Vec<(Range, String)> {
+  let mut references = Vec::new();
+  let mut callback = |link: BrokenLink| {
+    let range = match link.link_type {
+      LinkType::Reference => link.span,
+      // the span excludes the trailing brackets
+      LinkType::Collapsed => link.span.start..link.span.end + 2,
+      _ => return None,
+    };
+    references.push((range, link.reference.to_string()));
+    None
+  };
+  Parser::new_with_broken_link_callback(markdown_text, get_options(config), Some(&mut callback)).for_each(drop);
+  references
+}
+
+fn get_options(config: &Configuration) -> Options {
+  let mut options = Options::empty();
+  options.insert(Options::ENABLE_TABLES);
+  options.insert(Options::ENABLE_FOOTNOTES);
+  options.insert(Options::ENABLE_STRIKETHROUGH);
+  options.insert(Options::ENABLE_TASKLISTS);
+  options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+  options.insert(Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS);
+  options.insert(Options::ENABLE_MATH);
+  if config.heading_attributes {
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+  }
+  if config.punctuation == Punctuation::Typographic {
+    options.insert(Options::ENABLE_SMART_PUNCTUATION);
+  }
+  options
+}
+
 fn parse_references(
   last_event_end: Option<usize>,
   end: usize,
@@ -179,7 +252,7 @@ fn parse_event(event: Event, iterator: &mut EventIterator) -> Result<Node, Parse
     Event::Start(tag) => parse_start(tag, iterator),
     Event::End(_) => Ok(iterator.get_not_implemented()), // do nothing
     Event::Code(_) => parse_code(iterator).map(|x| x.into()),
-    Event::Text(_) => parse_text(iterator).map(|x| x.into()),
+    Event::Text(text) => parse_text(text, iterator).map(|x| x.into()),
     Event::Html(html) => parse_html(html, iterator).map(|x| x.into()),
     Event::InlineHtml(html) => parse_html(html, iterator).map(Into::into),
     Event::FootnoteReference(reference) => parse_footnote_reference(reference, iterator).map(|x| x.into()),
@@ -215,8 +288,24 @@ fn parse_event(event: Event, iterator: &mut EventIterator) -> Result<Node, Parse
 
 fn parse_start(start_tag: Tag, iterator: &mut EventIterator) -> Result<Node, ParseError> {
   match start_tag {
-    Tag::Heading { level, .. } => parse_heading(level, iterator).map(|x| x.into()),
-    Tag::Paragraph => parse_paragraph(iterator).map(|x| x.into()),
+    Tag::Heading {
+      level,
+      id,
+      classes,
+      attrs,
+    } => {
+      let attributes = HeadingAttributes {
+        range: Range::default(),
+        id: id.map(|id| id.to_string()),
+        classes: classes.into_iter().map(|c| c.to_string()).collect(),
+        attrs: attrs
+          .into_iter()
+          .map(|(key, value)| (key.to_string(), value.map(|v| v.to_string())))
+          .collect(),
+      };
+      parse_heading(level, attributes, iterator).map(|x| x.into())
+    }
+    Tag::Paragraph => parse_paragraph(iterator).map(|x| parse_definition_list(x, iterator)),
     Tag::BlockQuote(_) => parse_block_quote(iterator).map(|x| x.into()),
     Tag::CodeBlock(kind) => parse_code_block(kind, iterator).map(|x| x.into()),
     Tag::FootnoteDefinition(label) => parse_footnote_definition(label, iterator).map(|x| x.into()),
@@ -226,7 +315,16 @@ fn parse_start(start_tag: Tag, iterator: &mut EventIterator) -> Result<Node, Par
     Tag::TableCell => parse_table_cell(iterator).map(|x| x.into()),
     Tag::Emphasis => parse_text_decoration(TextDecorationKind::Emphasis, iterator).map(|x| x.into()),
     Tag::Strong => parse_text_decoration(TextDecorationKind::Strong, iterator).map(|x| x.into()),
-    Tag::Strikethrough => parse_text_decoration(TextDecorationKind::Strikethrough, iterator).map(|x| x.into()),
+    Tag::Strikethrough => {
+      // a single tilde is a subscript when enabled (ex. `~2~`)
+      let is_single_tilde = !iterator.file_text[iterator.start()..].starts_with("~~");
+      let kind = if iterator.config.subscript_superscript && is_single_tilde {
+        TextDecorationKind::Subscript
+      } else {
+        TextDecorationKind::Strikethrough
+      };
+      parse_text_decoration(kind, iterator).map(|x| x.into())
+    }
     Tag::Link {
       link_type,
       dest_url: destination_url,
@@ -241,7 +339,11 @@ fn parse_start(start_tag: Tag, iterator: &mut EventIterator) -> Result<Node, Par
   }
 }
 
-fn parse_heading(level: HeadingLevel, iterator: &mut EventIterator) -> Result<Heading, ParseError> {
+fn parse_heading(
+  level: HeadingLevel,
+  mut attributes: HeadingAttributes,
+  iterator: &mut EventIterator,
+) -> Result<Heading, ParseError> {
   let start = iterator.start();
   let mut children = Vec::new();
 
@@ -260,10 +362,27 @@ fn parse_heading(level: HeadingLevel, iterator: &mut EventIterator) -> Result<He
     }
   }
 
+  // pulldown cmark provides empty attributes when there's no attribute block,
+  // so check for the block after the heading's content (ex. `# Title {}`)
+  let range = iterator.get_range_for_start(start);
+  let content_end = children.last().map(|c| c.range().end).unwrap_or(range.start);
+  let line_end = iterator.file_text[content_end..range.end]
+    .find('\n')
+    .map(|index| content_end + index)
+    .unwrap_or(range.end);
+  let attributes_start = iterator.file_text[content_end..line_end]
+    .find('{')
+    .map(|index| content_end + index);
+  let attributes = attributes_start.map(|start| {
+    attributes.range = start..start + iterator.file_text[start..line_end].trim_end().len();
+    attributes
+  });
+
   Ok(Heading {
-    range: iterator.get_range_for_start(start),
+    range,
     level: level as u32,
-    children,
+    children: parse_text_extensions(children, iterator),
+    attributes,
   })
 }
 
@@ -287,11 +406,261 @@ fn parse_paragraph(iterator: &mut EventIterator) -> Result<Paragraph, ParseError
 
   Ok(Paragraph {
     range: iterator.get_range_for_start(start),
-    children,
+    children: parse_text_extensions(children, iterator),
     marker,
   })
 }
 
+/// Converts the paragraph to a definition list when enabled and its first line
+/// is followed by lines starting with a `:` (ex. `Term\n: Definition`).
+///
+/// This is only the inline form. Definitions that can be parsed as list items are
+/// converted afterwards in `convert_definition_lists` in order to support blocks.
+fn parse_definition_list(paragraph: Paragraph, iterator: &EventIterator) -> Node {
+  let is_definition_start = |node: Option<&Node>| node.is_some_and(|node| node.starts_with_definition_marker());
+  let is_break = |node: &Node| matches!(node, Node::SoftBreak(_) | Node::HardBreak(_));
+  let term_end = match paragraph.children.iter().position(is_break) {
+    Some(index) => index,
+    None => return paragraph.into(),
+  };
+  if !iterator.config.definition_lists
+    || paragraph.marker.is_some()
+    || term_end == 0
+    || !matches!(paragraph.children[term_end], Node::SoftBreak(_))
+    || is_definition_start(paragraph.children.first())
+    || !is_definition_start(paragraph.children.get(term_end + 1))
+  {
+    return paragraph.into();
+  }
+
+  let mut children = paragraph.children.into_iter();
+  let term_children = children.by_ref().take(term_end).collect::<Vec<_>>();
+  let mut definitions: Vec<Definition> = Vec::new();
+  let mut last_break: Option<Node> = None;
+  for node in children {
+    if is_break(&node) {
+      last_break = Some(node);
+      continue;
+    }
+    if last_break.is_some() && is_definition_start(Some(&node)) {
+      // the line break before a definition isn't kept
+      last_break = None;
+      if let Node::Text(text) = node {
+        definitions.push(Definition {
+          range: text.range.clone(),
+          children: get_sub_text(&text, text.range.start + 1..text.range.end, iterator)
+            .map(|text| vec![text.into()])
+            .unwrap_or_default(),
+        });
+      }
+      continue;
+    }
+    // the first line after the term is always a definition
+    let definition = definitions.last_mut().unwrap();
+    definition.range.end = node.range().end;
+    definition.children.extend(last_break.take());
+    definition.children.push(node);
+  }
+
+  DefinitionList {
+    range: paragraph.range,
+    term: DefinitionTerm {
+      range: term_children.first().unwrap().range().start..term_children.last().unwrap().range().end,
+      children: term_children,
+    },
+    definitions,
+  }
+  .into()
+}
+
+/// Gets the positions of the `:` of definitions that can be parsed as list items.
+fn get_definition_markers(nodes: &mut Vec<Node>, file_text: &str) -> Vec<usize> {
+  let mut markers = Vec::new();
+  for_each_block_children(nodes, &mut |nodes| {
+    for (i, node) in nodes.iter().enumerate() {
+      let previous = i.checked_sub(1).map(|i| &nodes[i]);
+      let mut definition_markers = match node {
+        // ex. `Term\n: Definition`
+        Node::DefinitionList(list) => {
+          // an empty item can't interrupt a paragraph
+          if list.definitions[0].children.is_empty() {
+            continue;
+          }
+          list.definitions.iter().map(|d| d.range.start).collect::<Vec<_>>()
+        }
+        // ex. `Term\n\n: Definition`
+        Node::Paragraph(paragraph)
+          if paragraph
+            .children
+            .first()
+            .is_some_and(|n| n.starts_with_definition_marker())
+            && match previous {
+              Some(Node::Paragraph(term)) => is_definition_term(term),
+              Some(Node::DefinitionList(_)) => true,
+              _ => false,
+            } =>
+        {
+          let mut last_node: Option<&Node> = None;
+          let mut definition_markers = Vec::new();
+          for node in &paragraph.children {
+            if (last_node.is_none() || matches!(last_node, Some(Node::SoftBreak(_))))
+              && node.starts_with_definition_marker()
+            {
+              definition_markers.push(node.range().start);
+            }
+            last_node = Some(node);
+          }
+          definition_markers
+        }
+        _ => continue,
+      };
+      definition_markers.retain(|&marker| file_text.as_bytes().get(marker) == Some(&b':'));
+      // the content of a definition could otherwise become a task list marker
+      if !definition_markers
+        .iter()
+        .any(|&marker| is_task_list_marker_text(&file_text[marker + 1..]))
+      {
+        markers.extend(definition_markers);
+      }
+    }
+  });
+  markers
+}
+
+fn is_task_list_marker_text(text: &str) -> bool {
+  let text = text.trim_start_matches([' ', '\t']);
+  ["[ ]", "[x]", "[X]"].iter().any(|marker| text.starts_with(marker))
+}
+
+fn is_definition_term(paragraph: &Paragraph) -> bool {
+  paragraph.marker.is_none()
+    && !paragraph.children.is_empty()
+    && !paragraph
+      .children
+      .iter()
+      .any(|node| matches!(node, Node::SoftBreak(_) | Node::HardBreak(_)))
+}
+
+/// Converts the lists whose items start with a replaced definition marker along
+/// with the term paragraph before them to definition lists. Returns the markers
+/// that were converted.
+fn convert_definition_lists(nodes: &mut Vec<Node>, markers: &[usize], file_text: &str) -> Vec<usize> {
+  let mut converted_markers = Vec::new();
+  if markers.is_empty() {
+    return converted_markers;
+  }
+  let is_definition_item = |node: &Node| matches!(node, Node::Item(item) if markers.contains(&item.range.start));
+
+  let is_definition_list =
+    |node: &Node| matches!(node, Node::List(list) if list.children.first().is_some_and(is_definition_item));
+
+  for_each_block_children(nodes, &mut |nodes| {
+    // the definitions of a term in a list item are parsed as its sub list
+    for node in nodes.iter_mut() {
+      if let Node::Item(item) = node {
+        if item.sub_lists.first().is_some_and(is_definition_list) {
+          // the term of a tight list item isn't in a paragraph
+          if let Some(paragraph) = get_inline_paragraph(&mut item.children) {
+            item.children.push(paragraph.into());
+          }
+          item.children.append(&mut item.sub_lists);
+        }
+      }
+    }
+
+    let mut result = Vec::with_capacity(nodes.len());
+    for node in std::mem::take(nodes) {
+      let list = match node {
+        Node::List(list)
+          if list.start_index.is_none()
+            && list.children.first().is_some_and(is_definition_item)
+            && matches!(result.last(), Some(Node::Paragraph(term)) if is_definition_term(term)) =>
+        {
+          list
+        }
+        _ => {
+          result.push(node);
+          continue;
+        }
+      };
+      let Some(Node::Paragraph(term)) = result.pop() else {
+        unreachable!();
+      };
+      let mut items = list.children.into_iter().peekable();
+      let mut definitions = Vec::new();
+      while let Some(Node::Item(item)) = items.next_if(is_definition_item) {
+        converted_markers.push(item.range.start);
+        let mut children = item.children;
+        children.extend(item.sub_lists);
+        let end = children.last().map(|c| c.range().end).unwrap_or(item.range.start + 1);
+        definitions.push(Definition {
+          // exclude any trailing blank lines
+          range: item.range.start..item.range.start + file_text[item.range.start..end].trim_end().len(),
+          children,
+        });
+      }
+      result.push(
+        DefinitionList {
+          range: term.range.start..definitions.last().unwrap().range.end,
+          term: DefinitionTerm {
+            range: term.children.first().unwrap().range().start..term.children.last().unwrap().range().end,
+            children: term.children,
+          },
+          definitions,
+        }
+        .into(),
+      );
+      // any remaining items were a separate list that used the same list character
+      let items = items.collect::<Vec<_>>();
+      if let Some(first) = items.first() {
+        result.push(
+          List {
+            range: first.range().start..list.range.end,
+            start_index: None,
+            children: items,
+          }
+          .into(),
+        );
+      }
+    }
+    *nodes = result;
+  });
+
+  // move any lists that are still at the end of list items back to their sub lists
+  for_each_block_children(nodes, &mut |nodes| {
+    for node in nodes.iter_mut() {
+      if let Node::Item(item) = node {
+        while matches!(item.children.last(), Some(Node::List(_))) {
+          item.sub_lists.insert(0, item.children.pop().unwrap());
+        }
+      }
+    }
+  });
+  converted_markers
+}
+
+/// Calls the function for each collection of block nodes in the tree.
+fn for_each_block_children(nodes: &mut Vec<Node>, func: &mut impl FnMut(&mut Vec<Node>)) {
+  func(nodes);
+  for node in nodes.iter_mut() {
+    match node {
+      Node::BlockQuote(node) => for_each_block_children(&mut node.children, func),
+      Node::FootnoteDefinition(node) => for_each_block_children(&mut node.children, func),
+      Node::List(node) => for_each_block_children(&mut node.children, func),
+      Node::Item(node) => {
+        for_each_block_children(&mut node.children, func);
+        for_each_block_children(&mut node.sub_lists, func);
+      }
+      Node::DefinitionList(node) => {
+        for definition in &mut node.definitions {
+          for_each_block_children(&mut definition.children, func);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
 fn parse_block_quote(iterator: &mut EventIterator) -> Result<BlockQuote, ParseError> {
   let start = iterator.start();
   let mut children = Vec::new();
@@ -303,12 +672,89 @@ fn parse_block_quote(iterator: &mut EventIterator) -> Result<BlockQuote, ParseEr
     }
   }
 
+  if let Some(callout) = parse_callout(&mut children, iterator) {
+    children.insert(0, callout.into());
+  }
+
   Ok(BlockQuote {
     range: iterator.get_range_for_start(start),
     children,
   })
 }
 
+/// Moves the first line of the block quote's first paragraph to a callout
+/// when it starts with a known callout kind (ex. `[!NOTE]`).
+fn parse_callout(children: &mut Vec<Node>, iterator: &EventIterator) -> Option<Callout> {
+  let paragraph = match children.first_mut() {
+    Some(Node::Paragraph(paragraph)) if paragraph.marker.is_none() => paragraph,
+    _ => return None,
+  };
+  let text = match paragraph.children.first() {
+    Some(Node::Text(text)) => text,
+    _ => return None,
+  };
+  let kind_end = text.text.find(']')?;
+  let kind = text.text.strip_prefix("[!")?.get(..kind_end - 2)?;
+  let (is_callout_kind, fold) = match iterator.config.callout_syntax {
+    CalloutSyntax::Github => {
+      let is_known_kind = |known_kind: &str| known_kind.eq_ignore_ascii_case(kind);
+      let is_callout_kind = GITHUB_CALLOUT_KINDS.into_iter().any(is_known_kind)
+        || iterator.config.custom_callout_kinds.iter().any(|k| is_known_kind(k));
+      (is_callout_kind, None)
+    }
+    CalloutSyntax::Obsidian => {
+      let is_callout_kind = !kind.is_empty() && kind.chars().all(is_callout_kind_char);
+      let fold = match text.text[kind_end + 1..].chars().next() {
+        Some('+') => Some(CalloutFold::Expanded),
+        Some('-') => Some(CalloutFold::Collapsed),
+        _ => None,
+      };
+      (is_callout_kind, fold)
+    }
+  };
+  let kind_end = if fold.is_some() { kind_end + 1 } else { kind_end };
+  let title_text = &text.text[kind_end + 1..];
+  if !is_callout_kind || !(title_text.is_empty() || title_text.starts_with(char::is_whitespace)) {
+    return None;
+  }
+
+  let kind = kind.to_string();
+  let title_start_offset = text.text.len() - title_text.trim_start().len();
+  let first_text = match paragraph.children.remove(0) {
+    Node::Text(text) => text,
+    _ => return None,
+  };
+  let mut nodes = paragraph.children.drain(..);
+  let mut title = Vec::new();
+  let callout_range = first_text.range.start..first_text.range.start + kind_end + 1;
+  let title_start = first_text.range.start + title_start_offset;
+  if let Some(title_text) = get_sub_text(&first_text, title_start..first_text.range.end, iterator) {
+    title.push(title_text.into());
+  }
+  for node in nodes.by_ref() {
+    if matches!(node, Node::SoftBreak(_) | Node::HardBreak(_)) {
+      break;
+    }
+    title.push(node);
+  }
+  let body = nodes.collect::<Vec<_>>();
+  let callout = Callout {
+    range: callout_range.start..title.last().map(|n| n.range().end).unwrap_or(callout_range.end),
+    kind,
+    fold,
+    title,
+  };
+
+  if body.is_empty() {
+    children.remove(0);
+  } else {
+    paragraph.range.start = body[0].range().start;
+    paragraph.children = body;
+  }
+
+  Some(callout)
+}
+
 fn parse_code_block(code_block_kind: CodeBlockKind, iterator: &mut EventIterator) -> Result<CodeBlock, ParseError> {
   let start = iterator.start();
   let mut code = String::new();
@@ -367,15 +813,20 @@ fn parse_code(iterator: &mut EventIterator) -> Result<Code, ParseError> {
   })
 }
 
-fn parse_text(iterator: &mut EventIterator) -> Result<Text, ParseError> {
+fn parse_text(text: CowStr, iterator: &mut EventIterator) -> Result<Text, ParseError> {
   // Pulldown cmark breaks up text items when they have escape chars
-  // in them, so just combine the results.
+  // or character references in them, so just combine the results.
+  let mut character_references = Vec::new();
+  let mut smart_punctuation = Vec::new();
+  add_decoded_text(&text, iterator, &mut character_references, &mut smart_punctuation);
   let raw_start = iterator.get_last_range().start;
   // Pulldown cmark has the start range in the wrong place when there's a leading backslash
   let is_start_backslash = raw_start > 0 && iterator.file_text.as_bytes()[raw_start - 1] == b'\\';
   let raw_start = if is_start_backslash { raw_start - 1 } else { raw_start };
   while let Some((Event::Text(_), _)) = iterator.peek() {
-    iterator.next();
+    if let Some(Event::Text(text)) = iterator.next() {
+      add_decoded_text(&text, iterator, &mut character_references, &mut smart_punctuation);
+    }
   }
   let raw_end = iterator.get_last_range().end;
 
@@ -389,9 +840,196 @@ fn parse_text(iterator: &mut EventIterator) -> Result<Text, ParseError> {
       end: start + trimmed_text.len(),
     },
     text: trimmed_text.replace("\r\n", "\n"),
+    character_references,
+    smart_punctuation,
   })
 }
 
+/// Gets the part of the text within the range, trimmed of any surrounding spaces.
+fn get_sub_text(text: &Text, range: Range, iterator: &EventIterator) -> Option<Text> {
+  let raw_text = &iterator.file_text[range.clone()];
+  let trimmed_text = trim_spaces_and_newlines(raw_text);
+  if trimmed_text.is_empty() {
+    return None;
+  }
+  let start = range.start + (raw_text.len() - trim_start_spaces_and_newlines(raw_text).len());
+  let range = start..start + trimmed_text.len();
+  let is_in_range = |r: &Range| r.start >= range.start && r.end <= range.end;
+
+  Some(Text {
+    text: trimmed_text.replace("\r\n", "\n"),
+    character_references: text
+      .character_references
+      .iter()
+      .filter(|r| is_in_range(&r.range))
+      .map(|r| CharacterReference {
+        range: r.range.clone(),
+        value: r.value.clone(),
+      })
+      .collect(),
+    smart_punctuation: text
+      .smart_punctuation
+      .iter()
+      .filter(|r| is_in_range(&r.range))
+      .map(|r| SmartPunctuation {
+        range: r.range.clone(),
+        value: r.value.clone(),
+      })
+      .collect(),
+    range,
+  })
+}
+
+/// Splits any wiki links (ex. `[[Page|alias]]`), superscripts (ex. `^2^`), and
+/// subscripts (ex. `~2~`) out of the text nodes when enabled.
+///
+/// Pulldown cmark doesn't support these, so they're parsed as text.
+fn parse_text_extensions(children: Vec<Node>, iterator: &EventIterator) -> Vec<Node> {
+  let config = iterator.config;
+  if !config.wiki_links && !config.subscript_superscript {
+    return children;
+  }
+
+  let mut nodes = Vec::with_capacity(children.len());
+  for child in children {
+    let text = match child {
+      Node::Text(text) => text,
+      _ => {
+        nodes.push(child);
+        continue;
+      }
+    };
+    let bytes = text.text.as_bytes();
+    let mut last_end = text.range.start;
+    let mut pos = 0;
+    while pos < bytes.len() {
+      if bytes[pos] == b'\\' {
+        pos += 2;
+        continue;
+      }
+      let start = text.range.start + pos;
+      let node = match bytes[pos] {
+        b'[' | b'!' if config.wiki_links => parse_wiki_link(&text.text, pos).map(|wiki_link| {
+          WikiLink {
+            range: start..start + wiki_link.range.end,
+            ..wiki_link
+          }
+          .into()
+        }),
+        b'^' | b'~' if config.subscript_superscript => parse_script(&text, pos, iterator).map(Node::from),
+        _ => None,
+      };
+      match node {
+        Some(node) => {
+          nodes.extend(get_sub_text(&text, last_end..start, iterator).map(Node::from));
+          last_end = node.range().end;
+          pos = last_end - text.range.start;
+          nodes.push(node);
+        }
+        None => pos += 1,
+      }
+    }
+    if last_end == text.range.start {
+      nodes.push(text.into());
+    } else {
+      nodes.extend(get_sub_text(&text, last_end..text.range.end, iterator).map(Node::from));
+    }
+  }
+  nodes
+}
+
+/// Parses a superscript (ex. `^2^`) or subscript (ex. `~2~`) starting at the position,
+/// which can't contain whitespace.
+fn parse_script(text: &Text, pos: usize, iterator: &EventIterator) -> Option<TextDecoration> {
+  let delimiter = text.text.as_bytes()[pos];
+  let inner_start = pos + 1;
+  let inner_len = text.text[inner_start..].find(|c: char| c == delimiter as char || c.is_whitespace())?;
+  if inner_len == 0 || text.text.as_bytes()[inner_start + inner_len] != delimiter {
+    return None;
+  }
+  let start = text.range.start + pos;
+  let inner_range = start + 1..start + 1 + inner_len;
+
+  Some(TextDecoration {
+    range: start..inner_range.end + 1,
+    kind: if delimiter == b'^' {
+      TextDecorationKind::Superscript
+    } else {
+      TextDecorationKind::Subscript
+    },
+    children: get_sub_text(text, inner_range, iterator)
+      .map(|text| vec![text.into()])
+      .unwrap_or_default(),
+  })
+}
+
+/// Parses a wiki link starting at the position. The returned range is relative to the position.
+fn parse_wiki_link(text: &str, pos: usize) -> Option<WikiLink> {
+  let text = text.get(pos..)?;
+  let (is_embed, inner_start) = if text.starts_with("![[") {
+    (true, 3)
+  } else if text.starts_with("[[") {
+    (false, 2)
+  } else {
+    return None;
+  };
+  let inner_end = inner_start + text[inner_start..].find(['[', ']'])?;
+  if !text[inner_end..].starts_with("]]") {
+    return None;
+  }
+  let inner_text = &text[inner_start..inner_end];
+  let (target, alias) = match inner_text.find('|') {
+    // the pipe is escaped in tables
+    Some(index) => (
+      inner_text[..index].strip_suffix('\\').unwrap_or(&inner_text[..index]),
+      Some(inner_text[index + 1..].trim()),
+    ),
+    None => (inner_text, None),
+  };
+  let (target, heading) = match target.find('#') {
+    Some(index) => (&target[..index], Some(target[index + 1..].trim())),
+    None => (target, None),
+  };
+  let target = target.trim();
+  if target.is_empty() && heading.is_none_or(|h| h.is_empty()) {
+    return None;
+  }
+
+  Some(WikiLink {
+    range: 0..inner_end + 2,
+    target: target.to_string(),
+    heading: heading.map(ToString::to_string),
+    alias: alias.map(ToString::to_string),
+    is_embed,
+  })
+}
+
+/// Pulldown cmark provides each character reference and smart punctuation
+/// replacement as its own text event with the decoded value.
+fn add_decoded_text(
+  text: &str,
+  iterator: &EventIterator,
+  character_references: &mut Vec<CharacterReference>,
+  smart_punctuation: &mut Vec<SmartPunctuation>,
+) {
+  let range = iterator.get_last_range();
+  let raw_text = &iterator.file_text[range.clone()];
+  if raw_text == text {
+    return;
+  }
+  if raw_text.starts_with('&') && raw_text.ends_with(';') {
+    character_references.push(CharacterReference {
+      range,
+      value: text.to_string(),
+    });
+  } else if !raw_text.is_empty() && raw_text.chars().all(|c| matches!(c, '"' | '\'' | '-' | '.')) {
+    smart_punctuation.push(SmartPunctuation {
+      range,
+      value: text.to_string(),
+    });
+  }
+}
+
 fn parse_text_decoration(kind: TextDecorationKind, iterator: &mut EventIterator) -> Result<TextDecoration, ParseError> {
   let start = iterator.start();
   let mut children = Vec::new();
@@ -408,7 +1046,7 @@ fn parse_text_decoration(kind: TextDecorationKind, iterator: &mut EventIterator)
   Ok(TextDecoration {
     range: iterator.get_range_for_start(start),
     kind,
-    children,
+    children: parse_text_extensions(children, iterator),
   })
 }
 
@@ -532,15 +1170,34 @@ fn parse_link(
 
 fn parse_image(link_type: LinkType, iterator: &mut EventIterator) -> Result<Node, ParseError> {
   let start = iterator.start();
+  let mut children = Vec::new();
 
   while let Some(event) = iterator.next() {
-    // ignore link children
-    if let Event::End(TagEnd::Image) = event {
-      break;
+    match event {
+      Event::End(TagEnd::Image) => break,
+      _ => children.push(parse_event(event, iterator)?),
     }
   }
 
-  parse_image_from_text(start, &iterator.file_text[start..], link_type)
+  // the alt text ends at the first closing bracket after its last child
+  let alt_text_end = children.last().map(|c| c.range().end).unwrap_or(start + 2);
+  let alt_text_end = match iterator.file_text[alt_text_end..].find(']') {
+    Some(index) => alt_text_end + index,
+    None => {
+      return Err(ParseError::new(
+        iterator.get_range_for_start(start),
+        "Did not find the end of the image alt text.",
+      ))
+    }
+  };
+
+  parse_image_from_text(
+    start,
+    alt_text_end,
+    &iterator.file_text[alt_text_end..],
+    link_type,
+    children,
+  )
 }
 
 fn parse_list(start_index: Option<u64>, iterator: &mut EventIterator) -> Result<List, ParseError> {
@@ -665,7 +1322,7 @@ fn parse_table_cell(iterator: &mut EventIterator) -> Result<TableCell, ParseErro
 
   Ok(TableCell {
     range: iterator.get_range_for_start(start),
-    children,
+    children: parse_text_extensions(children, iterator),
   })
 }
 
@@ -706,6 +1363,16 @@ fn parse_item(iterator: &mut EventIterator) -> Result<Item, ParseError> {
     children.push(references);
   }
 
+  // the text of a tight list item could be a definition list
+  if iterator.config.definition_lists {
+    if let Some(paragraph) = get_inline_paragraph(&mut children) {
+      children = match parse_definition_list(paragraph, iterator) {
+        Node::Paragraph(paragraph) => paragraph.children,
+        node => vec![node],
+      };
+    }
+  }
+
   Ok(Item {
     range,
     marker,
@@ -714,6 +1381,38 @@ fn parse_item(iterator: &mut EventIterator) -> Result<Item, ParseError> {
   })
 }
 
+/// Takes the nodes as a paragraph when they're all inline nodes.
+fn get_inline_paragraph(nodes: &mut Vec<Node>) -> Option<Paragraph> {
+  let is_inline = |node: &Node| {
+    matches!(
+      node,
+      Node::Text(_)
+        | Node::TextDecoration(_)
+        | Node::Code(_)
+        | Node::InlineLink(_)
+        | Node::ReferenceLink(_)
+        | Node::ShortcutLink(_)
+        | Node::AutoLink(_)
+        | Node::WikiLink(_)
+        | Node::InlineImage(_)
+        | Node::ReferenceImage(_)
+        | Node::InlineMath(_)
+        | Node::FootnoteReference(_)
+        | Node::SoftBreak(_)
+        | Node::HardBreak(_)
+    )
+  };
+  if nodes.is_empty() || !nodes.iter().all(is_inline) {
+    return None;
+  }
+  let children = std::mem::take(nodes);
+  Some(Paragraph {
+    range: children.first().unwrap().range().start..children.last().unwrap().range().end,
+    children,
+    marker: None,
+  })
+}
+
 fn parse_metadata(kind: MetadataBlockKind, iterator: &mut EventIterator) -> Result<MetadataBlock, ParseError> {
   let start = iterator.get_last_range().start;
   let mut text = String::new();
@@ -740,10 +1439,8 @@ fn parse_metadata(kind: MetadataBlockKind, iterator: &mut EventIterator) -> Resu
   }
 
   Ok(MetadataBlock {
-    range: Range {
-      start,
-      end: start + text.len(),
-    },
+    // include the delimiters
+    range: iterator.get_range_for_start(start),
     kind,
     text,
   })
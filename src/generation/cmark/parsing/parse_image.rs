@@ -4,31 +4,37 @@ use super::parse_text_in_parens;
 use crate::generation::common::*;
 use pulldown_cmark::LinkType;
 
-/// Crudely parses out an image assuming the text is an image.
+/// Crudely parses out the destination of an image assuming the text is an image.
 /// This is done because images have their references inlined by cmark.
-pub fn parse_image(offset: usize, text: &str, link_type: LinkType) -> Result<Node, ParseError> {
+///
+/// The provided text should start at the closing bracket of the image's alt text
+/// and the alt text should already be parsed into the provided children.
+pub fn parse_image(
+  start_pos: usize,
+  offset: usize,
+  text: &str,
+  link_type: LinkType,
+  children: Vec<Node>,
+) -> Result<Node, ParseError> {
   let mut char_scanner = CharScanner::new(offset, text);
-  let start_pos = char_scanner.pos();
 
   match link_type {
-    LinkType::Inline => parse_inline(start_pos, &mut char_scanner),
+    LinkType::Inline => parse_inline(start_pos, &mut char_scanner, children),
     LinkType::Reference | LinkType::ReferenceUnknown | LinkType::Collapsed | LinkType::CollapsedUnknown => {
-      parse_reference(start_pos, &mut char_scanner)
+      parse_reference(start_pos, &mut char_scanner, children)
     }
     LinkType::Shortcut | LinkType::ShortcutUnknown | LinkType::Email | LinkType::Autolink => Err(ParseError::new(
       Range {
-        start: offset,
-        end: offset,
+        start: start_pos,
+        end: start_pos,
       },
       format!("Link type not implemented {:?}", link_type),
     )),
   }
 }
 
-fn parse_inline(start_pos: usize, char_scanner: &mut CharScanner) -> Result<Node, ParseError> {
-  char_scanner.assert_char('!')?;
-  char_scanner.assert_char('[')?;
-  let text = parse_text_in_brackets(start_pos, char_scanner)?;
+fn parse_inline(start_pos: usize, char_scanner: &mut CharScanner, children: Vec<Node>) -> Result<Node, ParseError> {
+  char_scanner.assert_char(']')?;
   char_scanner.assert_char('(')?;
   let paren_text = parse_text_in_parens(start_pos, char_scanner)?;
   let (url, title) = parse_link_url_and_title(paren_text.trim());
@@ -39,7 +45,7 @@ fn parse_inline(start_pos: usize, char_scanner: &mut CharScanner) -> Result<Node
         start: start_pos,
         end: char_scanner.end(),
       },
-      text,
+      children,
       url,
       title,
     }
@@ -47,10 +53,8 @@ fn parse_inline(start_pos: usize, char_scanner: &mut CharScanner) -> Result<Node
   )
 }
 
-fn parse_reference(start_pos: usize, char_scanner: &mut CharScanner) -> Result<Node, ParseError> {
-  char_scanner.assert_char('!')?;
-  char_scanner.assert_char('[')?;
-  let text = parse_text_in_brackets(start_pos, char_scanner)?;
+fn parse_reference(start_pos: usize, char_scanner: &mut CharScanner, children: Vec<Node>) -> Result<Node, ParseError> {
+  char_scanner.assert_char(']')?;
   char_scanner.assert_char('[')?;
   let reference = parse_text_in_brackets(start_pos, char_scanner)?;
 
@@ -60,7 +64,7 @@ fn parse_reference(start_pos: usize, char_scanner: &mut CharScanner) -> Result<N
         start: start_pos,
         end: char_scanner.end(),
       },
-      text,
+      children,
       reference,
     }
     .into(),
@@ -73,7 +77,7 @@ mod test {
 
   #[test]
   fn it_should_parse_image() {
-    let result = parse_image(10, "![text](url)\n", LinkType::Inline);
+    let result = parse_image(10, 16, "](url)\n", LinkType::Inline, Vec::new());
     assert_eq!(result.is_ok(), true);
     let image = result.ok().unwrap();
     assert_eq!(image.range().start, 10);
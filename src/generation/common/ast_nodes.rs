@@ -24,6 +24,15 @@ pub struct Heading {
   pub range: Range,
   pub level: u32,
   pub children: Vec<Node>,
+  pub attributes: Option<HeadingAttributes>,
+}
+
+/// An attribute block at the end of a heading (ex. `{#custom-id .class key=value}`).
+pub struct HeadingAttributes {
+  pub range: Range,
+  pub id: Option<String>,
+  pub classes: Vec<String>,
+  pub attrs: Vec<(String, Option<String>)>,
 }
 
 pub struct Paragraph {
@@ -32,20 +41,77 @@ pub struct Paragraph {
   pub marker: Option<TaskListMarker>,
 }
 
+/// A definition list (ex. `Term\n: Definition`).
+pub struct DefinitionList {
+  pub range: Range,
+  pub term: DefinitionTerm,
+  pub definitions: Vec<Definition>,
+}
+
+pub struct DefinitionTerm {
+  pub range: Range,
+  pub children: Vec<Node>,
+}
+
+/// A definition of a term without its leading `:`.
+pub struct Definition {
+  pub range: Range,
+  pub children: Vec<Node>,
+}
+
 pub struct BlockQuote {
   pub range: Range,
   pub children: Vec<Node>,
 }
 
+/// The first line of a block quote that is a callout (ex. `> [!NOTE]`).
+pub struct Callout {
+  pub range: Range,
+  /// The kind as written (ex. `note` for `[!note]`).
+  pub kind: String,
+  /// The fold marker after the kind (ex. `-` for `[!info]-`).
+  pub fold: Option<CalloutFold>,
+  /// The nodes after the kind on the same line.
+  pub title: Vec<Node>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CalloutFold {
+  /// `+`
+  Expanded,
+  /// `-`
+  Collapsed,
+}
+
 pub struct Text {
   pub range: Range,
   pub text: String,
+  pub character_references: Vec<CharacterReference>,
+  pub smart_punctuation: Vec<SmartPunctuation>,
+}
+
+/// A character reference (ex. `&copy;` or `&#169;`) within text.
+pub struct CharacterReference {
+  pub range: Range,
+  /// The decoded value.
+  pub value: String,
+}
+
+/// Straight quotes, dashes, or an ellipsis within text that can be written
+/// as typographic characters (ex. `"` or `---`).
+pub struct SmartPunctuation {
+  pub range: Range,
+  /// The typographic value.
+  pub value: String,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub enum TextDecorationKind {
   Emphasis,
   Strong,
   Strikethrough,
+  Superscript,
+  Subscript,
 }
 
 pub struct TextDecoration {
@@ -100,6 +166,16 @@ pub struct AutoLink {
   pub children: Vec<Node>,
 }
 
+/// A wiki link (ex. `[[Page#Heading|alias]]`) or embed (ex. `![[image.png]]`).
+pub struct WikiLink {
+  pub range: Range,
+  pub target: String,
+  /// The heading or block anchor after the `#` (ex. `Heading` or `^block-id`).
+  pub heading: Option<String>,
+  pub alias: Option<String>,
+  pub is_embed: bool,
+}
+
 pub struct LinkReference {
   pub range: Range,
   pub name: String,
@@ -109,14 +185,14 @@ pub struct LinkReference {
 
 pub struct InlineImage {
   pub range: Range,
-  pub text: String,
+  pub children: Vec<Node>,
   pub url: String,
   pub title: Option<String>,
 }
 
 pub struct ReferenceImage {
   pub range: Range,
-  pub text: String,
+  pub children: Vec<Node>,
   pub reference: String,
 }
 
@@ -135,6 +211,11 @@ impl Text {
       result
     }
   }
+
+  /// Gets if the text starts with a definition marker (ex. `: Definition`).
+  pub fn starts_with_definition_marker(&self) -> bool {
+    self.text == ":" || self.text.starts_with(": ") || self.text.starts_with(":\t")
+  }
 }
 
 pub struct SoftBreak {
@@ -279,6 +360,14 @@ impl Node {
     }
   }
 
+  pub fn starts_with_definition_marker(&self) -> bool {
+    if let Node::Text(text) = self {
+      text.starts_with_definition_marker()
+    } else {
+      false
+    }
+  }
+
   pub fn has_preceding_space(&self, file_text: &str) -> bool {
     let range = self.range();
     if range.start == 0 {
@@ -314,7 +403,11 @@ generate_node![
   SourceFile,
   Heading,
   Paragraph,
+  DefinitionList,
+  DefinitionTerm,
+  Definition,
   BlockQuote,
+  Callout,
   Text,
   TextDecoration,
   Html,
@@ -324,6 +417,7 @@ generate_node![
   ReferenceLink,
   ShortcutLink,
   AutoLink,
+  WikiLink,
   LinkReference,
   InlineImage,
   ReferenceImage,
@@ -1,8 +1,10 @@
 mod ast_nodes;
 mod char_scanner;
+mod visit_nodes;
 
 pub use ast_nodes::*;
 pub use char_scanner::*;
+pub use visit_nodes::*;
 
 pub struct ParseError {
   /// This range the parse error occurred.
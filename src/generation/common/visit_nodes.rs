@@ -0,0 +1,44 @@
+use super::*;
+
+/// Calls the function for each node and its descendants in document order.
+pub fn visit_nodes_mut(nodes: &mut [Node], visit: &mut impl FnMut(&mut Node)) {
+  for node in nodes {
+    visit(node);
+    match node {
+      Node::SourceFile(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::Heading(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::Paragraph(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::DefinitionList(node) => {
+        visit_nodes_mut(&mut node.term.children, visit);
+        for definition in &mut node.definitions {
+          visit_nodes_mut(&mut definition.children, visit);
+        }
+      }
+      Node::BlockQuote(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::Callout(node) => visit_nodes_mut(&mut node.title, visit),
+      Node::TextDecoration(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::FootnoteDefinition(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::InlineLink(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::ReferenceLink(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::ShortcutLink(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::AutoLink(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::InlineImage(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::ReferenceImage(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::List(node) => visit_nodes_mut(&mut node.children, visit),
+      Node::Item(node) => {
+        visit_nodes_mut(&mut node.children, visit);
+        visit_nodes_mut(&mut node.sub_lists, visit);
+      }
+      Node::Table(node) => {
+        let rows = std::iter::once(&mut node.header.cells).chain(node.rows.iter_mut().map(|row| &mut row.cells));
+        for cells in rows {
+          for cell in cells {
+            visit_nodes_mut(&mut cell.children, visit);
+          }
+        }
+      }
+      Node::TableCell(node) => visit_nodes_mut(&mut node.children, visit),
+      _ => {}
+    }
+  }
+}
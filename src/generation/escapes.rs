@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+
+use super::utils::is_list_word;
+use crate::configuration::Configuration;
+
+/// Removes the backslash escapes in the provided text that are never
+/// necessary for the text to keep its meaning.
+///
+/// Escapes of characters that only have a meaning at the start of a line
+/// (ex. `-`, `#`, `>` or `1.`) are removed as well and should be added back
+/// based on where the word ends up being printed (see `get_line_start_escape_index`).
+pub fn remove_unnecessary_escapes<'a>(text: &'a str, config: &Configuration) -> Cow<'a, str> {
+  if !text.contains('\\') {
+    return Cow::Borrowed(text);
+  }
+
+  let chars = text.char_indices().collect::<Vec<_>>();
+  let mut result = String::with_capacity(text.len());
+  let mut i = 0;
+  while i < chars.len() {
+    let (byte_pos, c) = chars[i];
+    if c == '\\' {
+      if let Some((_, next_char)) = chars.get(i + 1).copied() {
+        if next_char.is_ascii_punctuation() {
+          let prev_char = if i == 0 { None } else { Some(chars[i - 1].1) };
+          let after_char = chars.get(i + 2).map(|(_, c)| *c);
+          if is_necessary_escape(next_char, prev_char, after_char, config) || is_hash_only_word(text, byte_pos) {
+            result.push('\\');
+          }
+          result.push(next_char);
+          i += 2;
+          continue;
+        }
+      }
+    }
+    result.push(c);
+    i += 1;
+  }
+
+  Cow::Owned(result)
+}
+
+fn is_necessary_escape(c: char, prev_char: Option<char>, next_char: Option<char>, config: &Configuration) -> bool {
+  match c {
+    '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '~' | '|' | '$' | '{' | '}' => true,
+    // could start a superscript
+    '^' => config.subscript_superscript,
+    // could start an image
+    '!' => next_char.is_none() || next_char == Some('['),
+    // could start a character reference
+    '&' => next_char.map(|c| c.is_alphanumeric() || c == '#').unwrap_or(true),
+    // could turn the preceding brackets into a link or link reference definition
+    '(' | ':' => prev_char.is_none() || prev_char == Some(']'),
+    _ => false,
+  }
+}
+
+/// A word of only `#` characters might be the closing sequence of a heading.
+fn is_hash_only_word(text: &str, backslash_pos: usize) -> bool {
+  let is_word_boundary = |c: char| c.is_whitespace();
+  let start = text[..backslash_pos]
+    .rfind(is_word_boundary)
+    .map(|i| i + 1)
+    .unwrap_or(0);
+  let end = text[backslash_pos..]
+    .find(is_word_boundary)
+    .map(|i| backslash_pos + i)
+    .unwrap_or(text.len());
+  text[start..end].chars().all(|c| c == '#' || c == '\\') && text[start..end].contains('#')
+}
+
+/// Gets the byte index a backslash should be inserted at in order for the
+/// word to not become markdown syntax when it is at the start of a line.
+pub fn get_line_start_escape_index(word: &str) -> Option<usize> {
+  let first_char = word.chars().next()?;
+  if is_list_word(word) {
+    // ex. `-` or `1.`
+    return Some(if first_char.is_ascii_digit() { word.len() - 1 } else { 0 });
+  }
+
+  let is_all_first_char = word.chars().all(|c| c == first_char);
+  let is_line_start_syntax = match first_char {
+    // block quote
+    '>' => true,
+    // heading, setext heading underline, or thematic break
+    '#' | '=' | '-' => is_all_first_char,
+    // thematic break
+    '*' | '_' => is_all_first_char && word.len() >= 3,
+    // code fence
+    '`' | '~' => word.starts_with("```") || word.starts_with("~~~"),
+    _ => false,
+  };
+  if is_line_start_syntax {
+    Some(0)
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::configuration::ConfigurationBuilder;
+
+  #[test]
+  fn it_should_remove_unnecessary_escapes() {
+    let config = ConfigurationBuilder::new().build();
+    assert_eq!(
+      remove_unnecessary_escapes("a \\. b \\- c \\# d", &config),
+      "a . b - c \\# d"
+    );
+    assert_eq!(
+      remove_unnecessary_escapes("C\\# \\\"quoted\\\"", &config),
+      "C# \"quoted\""
+    );
+    assert_eq!(
+      remove_unnecessary_escapes("\\*a\\* \\_b\\_ \\[c\\] \\`d\\`", &config),
+      "\\*a\\* \\_b\\_ \\[c\\] \\`d\\`"
+    );
+    assert_eq!(remove_unnecessary_escapes("\\![a] \\!b", &config), "\\![a] !b");
+    assert_eq!(remove_unnecessary_escapes("\\&amp; \\& b", &config), "\\&amp; & b");
+    assert_eq!(remove_unnecessary_escapes("]\\(a\\) b\\(c", &config), "]\\(a) b(c");
+    assert_eq!(remove_unnecessary_escapes("a \\## \\#b", &config), "a \\## #b");
+    assert_eq!(remove_unnecessary_escapes("a \\b", &config), "a \\b");
+    assert_eq!(remove_unnecessary_escapes("\\^a\\^", &config), "^a^");
+
+    let config = ConfigurationBuilder::new().subscript_superscript(true).build();
+    assert_eq!(
+      remove_unnecessary_escapes("\\^a\\^ \\~b\\~", &config),
+      "\\^a\\^ \\~b\\~"
+    );
+  }
+
+  #[test]
+  fn it_should_get_line_start_escape_index() {
+    assert_eq!(get_line_start_escape_index("-"), Some(0));
+    assert_eq!(get_line_start_escape_index("+"), Some(0));
+    assert_eq!(get_line_start_escape_index("10."), Some(2));
+    assert_eq!(get_line_start_escape_index("1)"), Some(1));
+    assert_eq!(get_line_start_escape_index("##"), Some(0));
+    assert_eq!(get_line_start_escape_index(">quote"), Some(0));
+    assert_eq!(get_line_start_escape_index("==="), Some(0));
+    assert_eq!(get_line_start_escape_index("***"), Some(0));
+    assert_eq!(get_line_start_escape_index("```js"), Some(0));
+    assert_eq!(get_line_start_escape_index("#1"), None);
+    assert_eq!(get_line_start_escape_index("1.5"), None);
+    assert_eq!(get_line_start_escape_index("-a"), None);
+    assert_eq!(get_line_start_escape_index("**"), None);
+  }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use super::common::*;
+use super::get_label_key;
+
+/// Renumbers the footnotes with numeric labels (ex. `[^7]`) in the order they're
+/// first referenced, followed by any definitions that aren't referenced.
+///
+/// Nothing is renumbered when an ignored top-level node has a footnote since it
+/// won't be updated.
+pub fn renumber_footnotes(nodes: &mut [Node], is_ignored: impl Fn(usize) -> bool) {
+  let mut has_ignored_footnote = false;
+  for i in (0..nodes.len()).filter(|i| is_ignored(*i)) {
+    visit_nodes_mut(&mut nodes[i..i + 1], &mut |node| {
+      has_ignored_footnote |= matches!(node, Node::FootnoteReference(_) | Node::FootnoteDefinition(_));
+    });
+  }
+  if has_ignored_footnote {
+    return;
+  }
+
+  let mut numbers = HashMap::new();
+  let mut add_label = |name: &str| {
+    if let Some(label) = get_numeric_label(name) {
+      let next_number = numbers.len() + 1;
+      numbers.entry(label.to_string()).or_insert(next_number);
+    }
+  };
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::FootnoteReference(reference) = node {
+      add_label(&reference.name);
+    }
+  });
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::FootnoteDefinition(definition) = node {
+      add_label(&definition.name);
+    }
+  });
+
+  let get_new_name = |name: &str| {
+    get_numeric_label(name)
+      .and_then(|label| numbers.get(label))
+      .map(|n| n.to_string())
+  };
+  visit_nodes_mut(nodes, &mut |node| match node {
+    Node::FootnoteReference(reference) => {
+      if let Some(name) = get_new_name(&reference.name) {
+        reference.name = name;
+      }
+    }
+    Node::FootnoteDefinition(definition) => {
+      if let Some(name) = get_new_name(&definition.name) {
+        definition.name = name;
+      }
+    }
+    _ => {}
+  });
+}
+
+/// Moves the top-level footnote definitions that aren't ignored to the end in the
+/// order they're first referenced, followed by any that aren't referenced.
+pub fn move_footnote_definitions_to_end(nodes: &mut Vec<Node>, is_ignored: impl Fn(usize) -> bool) {
+  let mut reference_order = HashMap::new();
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::FootnoteReference(reference) = node {
+      let next_index = reference_order.len();
+      reference_order
+        .entry(get_label_key(&reference.name))
+        .or_insert(next_index);
+    }
+  });
+
+  let mut definitions = Vec::new();
+  for (i, node) in std::mem::take(nodes).into_iter().enumerate() {
+    match node {
+      Node::FootnoteDefinition(definition) if !is_ignored(i) => definitions.push(definition),
+      _ => nodes.push(node),
+    }
+  }
+  definitions.sort_by_key(|definition| {
+    reference_order
+      .get(&get_label_key(&definition.name))
+      .copied()
+      .unwrap_or(usize::MAX)
+  });
+  nodes.extend(definitions.into_iter().map(Node::from));
+}
+
+fn get_numeric_label(name: &str) -> Option<&str> {
+  let name = name.trim();
+  if !name.is_empty() && name.chars().all(|c| c.is_ascii_digit()) {
+    Some(name)
+  } else {
+    None
+  }
+}
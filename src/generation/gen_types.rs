@@ -5,6 +5,8 @@ use dprint_core::formatting::PrintItems;
 use dprint_core::formatting::Signal;
 use regex::Regex;
 
+use super::common::Range;
+use super::common::TextDecorationKind;
 use super::utils::*;
 use crate::configuration::Configuration;
 use crate::format_text;
@@ -20,6 +22,16 @@ pub enum MemoizedRcPathKind {
   FinishIndent(u32),
 }
 
+/// A text decoration whose children are being generated.
+pub struct ParentTextDecoration {
+  pub kind: TextDecorationKind,
+  pub delimiter_char: char,
+  /// The range of the children in the source.
+  pub content_range: Range,
+  /// The kind to use for a single strong or emphasis child when the nesting order was swapped.
+  pub child_kind: Option<TextDecorationKind>,
+}
+
 pub struct Context<'a> {
   pub file_text: &'a str,
   pub configuration: &'a Configuration,
@@ -29,8 +41,21 @@ pub struct Context<'a> {
   pub raw_indent_level: u32,
   is_in_list_count: u32,
   is_in_block_quote_count: u32,
+  is_in_table_cell_count: u32,
   text_wrap_disabled_count: u32,
+  text_normalization_disabled_count: u32,
+  pub parent_text_decorations: Vec<ParentTextDecoration>,
+  /** The end position and delimiter character of the last generated text decoration. */
+  pub last_text_decoration: Option<(usize, char)>,
+  /** Whether the items of the current list are separated by blank lines or `None` to use the source. */
+  pub is_loose_list: Option<bool>,
+  /** The end position and list character of the last generated unordered list. */
+  pub last_list_char: Option<(usize, char)>,
+  /** The number of HTML elements opened by the previous HTML blocks that aren't closed yet. */
+  pub html_element_depth: u32,
   pub format_code_block_text: Box<dyn for<'b> FnMut(&str, &'b str, u32) -> FormatResult + 'a>,
+  /** Gets the tag provided to `format_code_block_text` for the info string of a code block. */
+  pub get_code_block_tag: fn(Option<&str>) -> Option<String>,
   pub ignore_regex: Regex,
   pub ignore_start_regex: Regex,
   pub ignore_end_regex: Regex,
@@ -50,8 +75,16 @@ impl<'a> Context<'a> {
       raw_indent_level: 0,
       is_in_list_count: 0,
       is_in_block_quote_count: 0,
+      is_in_table_cell_count: 0,
       text_wrap_disabled_count: 0,
+      text_normalization_disabled_count: 0,
+      parent_text_decorations: Vec::new(),
+      last_text_decoration: None,
+      is_loose_list: None,
+      last_list_char: None,
+      html_element_depth: 0,
       format_code_block_text: Box::new(format_code_block_text),
+      get_code_block_tag,
       ignore_regex: get_ignore_comment_regex(&configuration.ignore_directive),
       ignore_start_regex: get_ignore_comment_regex(&configuration.ignore_start_directive),
       ignore_end_regex: get_ignore_comment_regex(&configuration.ignore_end_directive),
@@ -113,6 +146,17 @@ impl<'a> Context<'a> {
     self.is_in_block_quote_count > 0
   }
 
+  pub fn mark_in_table_cell<T>(&mut self, func: impl FnOnce(&mut Context) -> T) -> T {
+    self.is_in_table_cell_count += 1;
+    let items = func(self);
+    self.is_in_table_cell_count -= 1;
+    items
+  }
+
+  pub fn is_in_table_cell(&self) -> bool {
+    self.is_in_table_cell_count > 0
+  }
+
   pub fn with_no_text_wrap<T>(&mut self, func: impl FnOnce(&mut Context) -> T) -> T {
     self.text_wrap_disabled_count += 1;
     let items = func(self);
@@ -124,6 +168,17 @@ impl<'a> Context<'a> {
     self.text_wrap_disabled_count > 0
   }
 
+  pub fn with_no_text_normalization<T>(&mut self, func: impl FnOnce(&mut Context) -> T) -> T {
+    self.text_normalization_disabled_count += 1;
+    let items = func(self);
+    self.text_normalization_disabled_count -= 1;
+    items
+  }
+
+  pub fn is_text_normalization_disabled(&self) -> bool {
+    self.text_normalization_disabled_count > 0
+  }
+
   pub fn format_text<'b>(&mut self, tag: &str, text: &'b str) -> FormatResult {
     let line_width = std::cmp::max(10, self.configuration.line_width as i32 - self.indent_level as i32) as u32;
 
@@ -5,11 +5,15 @@ use dprint_core::formatting::*;
 use dprint_core_macros::sc;
 use pulldown_cmark::MetadataBlockKind;
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::rc::Rc;
 use unicode_width::UnicodeWidthStr;
 
+use super::character_references::*;
 use super::common::*;
+use super::escapes::*;
 use super::gen_types::*;
+use super::punctuation::*;
 use super::utils;
 use crate::configuration::*;
 
@@ -21,10 +25,14 @@ pub fn generate(node: &Node, context: &mut Context) -> PrintItems {
     Node::SourceFile(node) => gen_source_file(node, context),
     Node::Heading(node) => gen_heading(node, context),
     Node::Paragraph(node) => gen_paragraph(node, context),
+    Node::DefinitionList(node) => gen_definition_list(node, context),
+    Node::DefinitionTerm(_) => unreachable!(),
+    Node::Definition(_) => unreachable!(),
     Node::BlockQuote(node) => gen_block_quote(node, context),
+    Node::Callout(node) => gen_callout(node, context),
     Node::CodeBlock(node) => gen_code_block(node, context),
     Node::Code(node) => gen_code(node, context),
-    Node::Text(node) => gen_text(node, context),
+    Node::Text(node) => gen_text(node, None, context),
     Node::TextDecoration(node) => gen_text_decoration(node, context),
     Node::Html(node) => gen_html(node, context),
     Node::DisplayMath(node) => gen_display_math(node, context),
@@ -35,6 +43,7 @@ pub fn generate(node: &Node, context: &mut Context) -> PrintItems {
     Node::ReferenceLink(node) => gen_reference_link(node, context),
     Node::ShortcutLink(node) => gen_shortcut_link(node, context),
     Node::AutoLink(node) => gen_auto_link(node, context),
+    Node::WikiLink(node) => gen_wiki_link(node, context),
     Node::LinkReference(node) => gen_link_reference(node, context),
     Node::InlineImage(node) => gen_inline_image(node, context),
     Node::ReferenceImage(node) => gen_reference_image(node, context),
@@ -72,6 +81,7 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
   if nodes.is_empty() {
     return items;
   }
+  let parent_html_element_depth = context.html_element_depth;
 
   let mut last_node: Option<&Node> = None;
   let mut node_iterator = nodes.iter().filter(|n| !matches!(n, Node::SoftBreak(_)));
@@ -81,7 +91,7 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
     if let Some(Node::List(last_list)) = &last_node {
       if let Node::List(list) = &node {
         if last_list.start_index.is_some() == list.start_index.is_some() {
-          items.extend(get_conditional_blank_line(node.range(), context));
+          items.extend(get_conditional_blank_line(node, context));
           items.extend(gen_list(list, true, context));
           if let Some(current_node) = node_iterator.next() {
             last_node = Some(node);
@@ -93,12 +103,30 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
       }
     }
 
+    // mark the line before the separator so the text can be escaped if it starts a new line
+    let mut separator_line = None;
+    if (context.configuration.escapes == Escapes::Minimal || context.configuration.punctuation == Punctuation::Ascii)
+      && matches!(node, Node::Text(_))
+      && last_node.is_some_and(|n| !matches!(n, Node::HardBreak(_)))
+    {
+      let line = Rc::new(Cell::new(0));
+      items.extend(gen_line_marker(&line));
+      separator_line = Some(line);
+    }
+
     // todo: this area needs to be thought out more
     if let Some(last_node) = last_node {
-      if matches!(
+      if let Node::Callout(callout) = last_node {
+        // the body of a callout always starts on the next line
+        items.push_signal(Signal::NewLine);
+        if context.get_new_lines_in_range(callout.range.end, node.range().start) > 1 {
+          items.push_signal(Signal::NewLine);
+        }
+      } else if matches!(
         node,
         Node::Heading(_)
           | Node::Paragraph(_)
+          | Node::DefinitionList(_)
           | Node::CodeBlock(_)
           | Node::FootnoteDefinition(_)
           | Node::HorizontalRule(_)
@@ -106,11 +134,12 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
           | Node::Table(_)
           | Node::BlockQuote(_)
       ) {
-        items.extend(get_conditional_blank_line(node.range(), context));
+        items.extend(get_conditional_blank_line(node, context));
       } else if !matches!(node, Node::HardBreak(_)) {
         match last_node {
           Node::Heading(_)
           | Node::Paragraph(_)
+          | Node::DefinitionList(_)
           | Node::CodeBlock(_)
           | Node::FootnoteDefinition(_)
           | Node::HorizontalRule(_)
@@ -119,7 +148,7 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
           | Node::MetadataBlock(_)
           | Node::BlockQuote(_)
           | Node::DisplayMath(_) => {
-            items.extend(get_conditional_blank_line(node.range(), context));
+            items.extend(get_conditional_blank_line(node, context));
           }
           Node::Code(_)
           | Node::SoftBreak(_)
@@ -129,6 +158,7 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
           | Node::ReferenceLink(_)
           | Node::ShortcutLink(_)
           | Node::AutoLink(_)
+          | Node::WikiLink(_)
           | Node::Text(_)
           | Node::Html(_)
           | Node::InlineImage(_)
@@ -138,28 +168,24 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
             let new_line_count = context.get_new_lines_in_range(between_range.0, between_range.1);
 
             if new_line_count == 1 {
-              // Callout example:
-              // > [!NOTE]
-              // > Some note.
-              let is_callout = if context.is_in_block_quote() {
-                if let Node::Text(text) = last_node {
-                  is_callout_text(&text.text)
-                } else {
-                  false
-                }
-              } else {
-                false
-              };
-              if is_callout && !context.is_text_wrap_disabled() {
+              // a callout marker with a kind that isn't recognized stays on its own line
+              // > [!UNKNOWN]
+              // > Some text.
+              let is_callout_marker = context.is_in_block_quote()
+                && matches!(last_node, Node::Text(text) if is_callout_marker_text(&text.text));
+              if is_callout_marker && !context.is_text_wrap_disabled() {
                 items.push_signal(Signal::NewLine); // force a newline
               } else if matches!(node, Node::Html(_)) {
                 items.push_signal(Signal::NewLine);
+              } else if is_definition_marker_start(node, context) && context.configuration.text_wrap == TextWrap::Always
+              {
+                // don't let the text move to where it would become a definition
+                items.push_space();
               } else {
                 items.extend(get_newline_wrapping_based_on_config(context));
               }
             } else if new_line_count > 1 {
-              items.push_signal(Signal::NewLine);
-              items.push_signal(Signal::NewLine);
+              items.extend(get_conditional_blank_line(node, context));
             } else {
               let needs_space = if let Node::Html(_) = last_node {
                 node.has_preceding_space(context.file_text)
@@ -167,6 +193,11 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
                 node.has_preceding_space(context.file_text)
                   || !last_node.ends_with_punctuation(context.file_text)
                     && !node.starts_with_punctuation(context.file_text)
+              } else if matches!(last_node, Node::TextDecoration(_) | Node::WikiLink(_))
+                || matches!(node, Node::TextDecoration(_) | Node::WikiLink(_))
+              {
+                // ex. `*a*_b_` is different than `*a* _b_`
+                node.has_preceding_space(context.file_text)
               } else if let Node::FootnoteReference(_) = node {
                 false
               } else if let Node::Html(_) = node {
@@ -176,7 +207,9 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
               };
 
               if needs_space {
-                if node.starts_with_list_word() {
+                if node.starts_with_list_word() && context.configuration.escapes == Escapes::Preserve
+                  || is_definition_marker_start(node, context)
+                {
                   items.push_space();
                 } else {
                   items.extend(get_space_or_newline_based_on_config(context));
@@ -187,14 +220,24 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
           Node::LinkReference(_) => {
             let needs_newline = matches!(node, Node::LinkReference(_));
             if needs_newline {
+              if context.configuration.link_reference_spacing == LinkReferenceSpacing::Preserve {
+                let count = utils::get_leading_blank_line_count(node.range().start, context.file_text)
+                  .min(context.configuration.max_blank_lines);
+                for _ in 0..count {
+                  items.push_signal(Signal::NewLine);
+                }
+              }
               items.push_signal(Signal::NewLine);
             }
           }
           Node::NotImplemented(_)
           | Node::SourceFile(_)
+          | Node::Callout(_)
           | Node::Item(_)
           | Node::TaskListMarker(_)
           | Node::HardBreak(_)
+          | Node::DefinitionTerm(_)
+          | Node::Definition(_)
           | Node::TableHead(_)
           | Node::TableRow(_)
           | Node::TableCell(_) => {}
@@ -202,9 +245,19 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
       }
     }
 
-    items.extend(generate(node, context));
+    items.extend(match node {
+      Node::Text(text) => gen_text(text, separator_line, context),
+      _ => generate(node, context),
+    });
     last_node = Some(node);
 
+    // an HTML block ends at a blank line, so an element with blank lines in it
+    // is separate blocks with the other blocks between them
+    if let Node::Html(html) = node {
+      let depth_change = utils::get_html_element_depth_change(&context.file_text[html.range.clone()]);
+      context.html_element_depth = context.html_element_depth.saturating_add_signed(depth_change);
+    }
+
     // check for ignore comment
     if let Node::Html(html) = node {
       let html_text = &context.file_text[html.range.clone()];
@@ -260,24 +313,52 @@ fn gen_nodes(nodes: &[Node], context: &mut Context) -> PrintItems {
     }
   }
 
+  context.html_element_depth = parent_html_element_depth;
   return items;
 
-  fn get_conditional_blank_line(range: &Range, context: &mut Context) -> PrintItems {
+  fn get_conditional_blank_line(node: &Node, context: &mut Context) -> PrintItems {
     let mut items = PrintItems::new();
-    if !context.is_in_list() || utils::has_leading_blankline(range.start, context.file_text) {
-      items.push_signal(Signal::NewLine);
+    if !context.is_in_list() || utils::has_leading_blankline(node.range().start, context.file_text) {
+      for _ in 0..get_blank_line_count(node, context) {
+        items.push_signal(Signal::NewLine);
+      }
     }
     items.push_signal(Signal::NewLine);
     items
   }
 }
 
+/// Gets the number of blank lines to keep before a block based on the source
+/// and the configuration.
+fn get_blank_line_count(node: &Node, context: &Context) -> u32 {
+  let config = context.configuration;
+  let max_blank_lines = match config.max_blank_lines_in_html {
+    Some(max_blank_lines) if context.html_element_depth > 0 => max_blank_lines,
+    _ => config.max_blank_lines,
+  };
+  let count = utils::get_leading_blank_line_count(node.range().start, context.file_text)
+    .clamp(1, std::cmp::max(max_blank_lines, 1));
+  match node {
+    Node::Heading(heading) if heading.level <= config.blank_lines_before_headings_max_level => {
+      std::cmp::max(count, config.blank_lines_before_headings)
+    }
+    _ => count,
+  }
+}
+
 fn gen_heading(heading: &Heading, context: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
 
   if heading.level < 3 && context.configuration.heading_kind == HeadingKind::Setext {
     // setext headings only apply to level 1 and level 2.
-    let heading_children = gen_nodes(&heading.children, context);
+    let mut heading_children = gen_nodes(&heading.children, context);
+    if let Some(attributes) = &heading.attributes {
+      // keep the attributes attached to the last line of the heading text
+      if !heading.children.is_empty() {
+        heading_children.push_space();
+      }
+      heading_children.push_string(get_heading_attributes_text(attributes, context));
+    }
     let (heading_children, cloned_children) = clone_items(heading_children);
     items.extend(heading_children);
     items.push_item(PrintItem::Signal(Signal::NewLine));
@@ -291,11 +372,50 @@ fn gen_heading(heading: &Heading, context: &mut Context) -> PrintItems {
     // atx headings apply to all levels.
     items.push_string(format!("{} ", "#".repeat(heading.level as usize)));
     items.extend(with_no_new_lines(gen_nodes(&heading.children, context)));
+    if let Some(attributes) = &heading.attributes {
+      if !heading.children.is_empty() {
+        items.push_space();
+      }
+      items.push_string(get_heading_attributes_text(attributes, context));
+    }
   }
 
   items
 }
 
+fn get_heading_attributes_text(attributes: &HeadingAttributes, context: &Context) -> String {
+  let mut parts = Vec::with_capacity(attributes.classes.len() + attributes.attrs.len() + 1);
+  if let Some(id) = &attributes.id {
+    parts.push(format!("#{}", id));
+  }
+  for class in &attributes.classes {
+    parts.push(format!(".{}", class));
+  }
+  for (key, value) in &attributes.attrs {
+    match value {
+      Some(value) => parts.push(format!("{}={}", key, value)),
+      None => parts.push(key.to_string()),
+    }
+  }
+
+  // pulldown cmark drops what it doesn't understand (ex. `{"a": 1}`),
+  // so only use the canonical text when nothing would be lost
+  let raw_text = &context.file_text[attributes.range.clone()];
+  let raw_inner_text = raw_text.strip_prefix('{').and_then(|t| t.strip_suffix('}'));
+  let mut raw_parts = raw_inner_text
+    .unwrap_or_default()
+    .split_whitespace()
+    .collect::<Vec<_>>();
+  let mut sorted_parts = parts.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+  raw_parts.sort_unstable();
+  sorted_parts.sort_unstable();
+  if raw_inner_text.is_some() && raw_parts == sorted_parts {
+    format!("{{{}}}", parts.join(" "))
+  } else {
+    raw_text.to_string()
+  }
+}
+
 fn gen_paragraph(paragraph: &Paragraph, context: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
 
@@ -314,6 +434,33 @@ fn gen_paragraph(paragraph: &Paragraph, context: &mut Context) -> PrintItems {
   items
 }
 
+fn gen_definition_list(definition_list: &DefinitionList, context: &mut Context) -> PrintItems {
+  let mut items = PrintItems::new();
+  // the term needs to stay on a single line
+  items
+    .extend(context.with_no_text_wrap(|context| with_no_new_lines(gen_nodes(&definition_list.term.children, context))));
+  let mut last_end = definition_list.term.range.end;
+  for definition in &definition_list.definitions {
+    items.push_signal(Signal::NewLine);
+    // keep loose definitions separated by a blank line
+    if context.get_new_lines_in_range(last_end, definition.range.start) > 1 {
+      items.push_signal(Signal::NewLine);
+    }
+    if definition.children.is_empty() {
+      items.push_sc(sc!(":"));
+    } else {
+      items.push_sc(sc!(": "));
+      context.indent_level += 2;
+      // like in list items, only keep blank lines between blocks that are in the source
+      let generated_children = context.mark_in_list(|context| gen_nodes(&definition.children, context));
+      items.extend(with_indent_times(generated_children, 2));
+      context.indent_level -= 2;
+    }
+    last_end = definition.range.end;
+  }
+  items
+}
+
 fn gen_block_quote(block_quote: &BlockQuote, context: &mut Context) -> PrintItems {
   context.mark_in_block_quotes(|context, block_quote_count| {
     let mut items = PrintItems::new();
@@ -370,6 +517,25 @@ fn gen_block_quote(block_quote: &BlockQuote, context: &mut Context) -> PrintItem
   })
 }
 
+fn gen_callout(callout: &Callout, context: &mut Context) -> PrintItems {
+  let mut items = PrintItems::new();
+  let kind = match context.configuration.callout_syntax {
+    CalloutSyntax::Github => callout.kind.to_uppercase(),
+    CalloutSyntax::Obsidian => callout.kind.clone(),
+  };
+  let fold = match callout.fold {
+    Some(CalloutFold::Expanded) => "+",
+    Some(CalloutFold::Collapsed) => "-",
+    None => "",
+  };
+  items.push_string(format!("[!{}]{}", kind, fold));
+  if !callout.title.is_empty() {
+    items.push_space();
+    items.extend(context.with_no_text_wrap(|context| gen_nodes(&callout.title, context)));
+  }
+  items
+}
+
 fn gen_code_block(code_block: &CodeBlock, context: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
   let code_text = get_code_text(code_block, context);
@@ -408,9 +574,7 @@ fn gen_code_block(code_block: &CodeBlock, context: &mut Context) -> PrintItems {
     }
     let start_pos = get_code_block_start_pos(code);
     let code = code[start_pos..].trim_end();
-    if let Some(tag) = &code_block.tag {
-      // allow situations like ```rust,ignore
-      let tag = tag.chars().take_while(|&c| c != ' ' && c != ',').collect::<String>();
+    if let Some(tag) = (context.get_code_block_tag)(code_block.tag.as_deref()) {
       if let Ok(Some(text)) = context.format_text(&tag, code) {
         return Cow::Owned(text);
       }
@@ -461,17 +625,94 @@ fn gen_code(code: &Code, _: &mut Context) -> PrintItems {
   format!("{0}{1}{2}{1}{0}", backtick_text, separator, text).into()
 }
 
-fn gen_text(text: &Text, context: &mut Context) -> PrintItems {
-  gen_str(&text.text, context)
+fn is_callout_marker_text(text: &str) -> bool {
+  // ex. [!NOTE]
+  text.starts_with("[!") && text.ends_with(']') && text[2..text.len() - 1].chars().all(|c| c.is_ascii_uppercase())
 }
 
-fn is_callout_text(text: &str) -> bool {
-  // ex. [!NOTE]
-  text.starts_with("[!") && text.ends_with("]") && text[2..text.len() - 1].chars().all(|c| c.is_ascii_uppercase())
+fn gen_text(text: &Text, separator_line: Option<Rc<Cell<u32>>>, context: &mut Context) -> PrintItems {
+  let first_word_escape = if let Some(line) = separator_line {
+    LineStartEscape::IfWrapped(line)
+  } else if utils::is_block_content_start(text.range.start, context.file_text) {
+    LineStartEscape::Always
+  } else {
+    LineStartEscape::Never
+  };
+  if context.is_text_normalization_disabled() {
+    return gen_str(&text.text, first_word_escape, context);
+  }
+  let normalized_text = normalize_text(text, context.configuration);
+  let text = match context.configuration.escapes {
+    Escapes::Minimal => remove_unnecessary_escapes(&normalized_text, context.configuration),
+    Escapes::Preserve => Cow::Borrowed(normalized_text.as_ref()),
+  };
+  gen_str(&text, first_word_escape, context)
+}
+
+/// Applies the configured character reference and punctuation changes to the text.
+fn normalize_text<'a>(text: &'a Text, config: &Configuration) -> Cow<'a, str> {
+  if config.character_references == CharacterReferences::Preserve && config.punctuation == Punctuation::Preserve {
+    return Cow::Borrowed(&text.text);
+  }
+
+  let mut replacements = Vec::with_capacity(text.character_references.len() + text.smart_punctuation.len());
+  for reference in &text.character_references {
+    if let Some(raw_text) = get_raw_sub_text(text, &reference.range) {
+      let reference_text = get_character_reference_text(reference, raw_text, config.character_references);
+      replacements.push((&reference.range, reference_text));
+    }
+  }
+  for punctuation in &text.smart_punctuation {
+    if get_raw_sub_text(text, &punctuation.range).is_some() {
+      replacements.push((&punctuation.range, Cow::Borrowed(punctuation.value.as_str())));
+    }
+  }
+  replacements.sort_by_key(|(range, _)| range.start);
+
+  let push_literal_text = |result: &mut String, literal_text: &str| {
+    let literal_text = match config.punctuation {
+      Punctuation::Ascii => to_ascii_punctuation(literal_text),
+      Punctuation::Preserve | Punctuation::Typographic => Cow::Borrowed(literal_text),
+    };
+    push_literal_text(result, &literal_text, config.character_references);
+  };
+  let mut result = String::with_capacity(text.text.len());
+  let mut last_end = 0;
+  for (range, replacement) in replacements {
+    let start = range.start - text.range.start;
+    if start < last_end {
+      continue;
+    }
+    push_literal_text(&mut result, &text.text[last_end..start]);
+    push_literal_text(&mut result, &replacement);
+    last_end = range.end - text.range.start;
+  }
+  push_literal_text(&mut result, &text.text[last_end..]);
+  return Cow::Owned(result);
+
+  fn get_raw_sub_text<'a>(text: &'a Text, range: &Range) -> Option<&'a str> {
+    let raw_text = text
+      .text
+      .get(range.start - text.range.start..range.end - text.range.start)?;
+    // the text could differ when it was modified (ex. \r\n to \n)
+    if raw_text.starts_with(['&', '"', '\'', '-', '.']) {
+      Some(raw_text)
+    } else {
+      None
+    }
+  }
 }
 
-fn gen_str(text: &str, context: &mut Context) -> PrintItems {
-  let mut text_builder = TextBuilder::new(context);
+/// When a word should be escaped in order to not become syntax at the start of a line.
+enum LineStartEscape {
+  Never,
+  Always,
+  /// When the word ends up on a later line than the one marked by `gen_line_marker`.
+  IfWrapped(Rc<Cell<u32>>),
+}
+
+fn gen_str(text: &str, first_word_escape: LineStartEscape, context: &mut Context) -> PrintItems {
+  let mut text_builder = TextBuilder::new(first_word_escape, context);
 
   for c in text.chars() {
     text_builder.add_char(c);
@@ -483,15 +724,17 @@ fn gen_str(text: &str, context: &mut Context) -> PrintItems {
     items: PrintItems,
     was_last_newline: bool,
     current_word: Option<String>,
+    first_word_escape: Option<LineStartEscape>,
     context: &'a Context<'a>,
   }
 
   impl<'a> TextBuilder<'a> {
-    pub fn new(context: &'a Context) -> TextBuilder<'a> {
+    pub fn new(first_word_escape: LineStartEscape, context: &'a Context) -> TextBuilder<'a> {
       TextBuilder {
         items: PrintItems::new(),
         was_last_newline: false,
         current_word: None,
+        first_word_escape: Some(first_word_escape),
         context,
       }
     }
@@ -531,71 +774,245 @@ fn gen_str(text: &str, context: &mut Context) -> PrintItems {
 
     fn flush_current_word(&mut self) {
       if let Some(current_word) = self.current_word.take() {
-        if !self.items.is_empty() {
-          if utils::is_list_word(&current_word) {
-            self.items.push_space();
-          } else if self.was_last_newline {
-            self.items.push_signal(Signal::NewLine)
-          } else {
-            self.items.extend(get_space_or_newline_based_on_config(self.context));
+        let is_minimal_escapes = self.context.configuration.escapes == Escapes::Minimal;
+        let mut line_start_escape = LineStartEscape::Never;
+        if self.items.is_empty() {
+          line_start_escape = self.first_word_escape.take().unwrap_or(LineStartEscape::Never);
+        } else if !is_minimal_escapes && utils::is_list_word(&current_word)
+          || self.context.configuration.definition_lists && current_word == ":"
+        {
+          // never wrap these words to the start of a line
+          self.items.push_space();
+        } else if self.was_last_newline {
+          self.items.push_signal(Signal::NewLine);
+          line_start_escape = LineStartEscape::Always;
+        } else {
+          if has_line_start_escape(&current_word, self.context) && is_space_or_newline_wrapping(self.context) {
+            let line = Rc::new(Cell::new(0));
+            self.items.extend(gen_line_marker(&line));
+            line_start_escape = LineStartEscape::IfWrapped(line);
           }
+          self.items.extend(get_space_or_newline_based_on_config(self.context));
         }
 
-        self.items.push_string(current_word);
+        if has_line_start_escape(&current_word, self.context) {
+          self
+            .items
+            .extend(gen_word_with_line_start_escape(current_word, line_start_escape));
+        } else {
+          self.items.push_string(current_word);
+        }
         self.was_last_newline = false;
       }
     }
   }
 }
 
-fn gen_text_decoration(text: &TextDecoration, context: &mut Context) -> PrintItems {
-  /// GitHub doesn't make `_` and `__` as being a text decoration when the character
-  /// after the underscore is alphanumeric. For example: `__word__something`. Due
-  /// to this, we need to keep the asterisk when configured for underscores
-  /// in order to ensure the text keeps its meaning on GitHub.
-  fn keep_asterisk(pos: usize, context: &Context) -> bool {
-    &context.file_text[pos - 1..pos] == "*"
-      && context.file_text[pos..]
-        .chars()
-        .next()
-        .map(|c| c.is_alphanumeric())
-        .unwrap_or(false)
-  }
+/// Gets if the node would start a definition when at the start of a line.
+fn is_definition_marker_start(node: &Node, context: &Context) -> bool {
+  context.configuration.definition_lists && node.starts_with_definition_marker()
+}
+
+/// Gets if the word should be escaped when it ends up at the start of a line.
+fn has_line_start_escape(word: &str, context: &Context) -> bool {
+  !context.is_text_normalization_disabled()
+    && (context.configuration.escapes == Escapes::Minimal
+      // ex. a dash converted to `---` could become a thematic break
+      || context.configuration.punctuation == Punctuation::Ascii && word.chars().all(|c| c == '-'))
+}
+
+/// Stores the line number it's printed on in the provided cell. This is used
+/// instead of a `LineNumber` info because infos can't be used in items that
+/// are measured by printing them separately (ex. setext headings).
+fn gen_line_marker(line: &Rc<Cell<u32>>) -> PrintItems {
+  let line = line.clone();
+  if_true(
+    "markLineNumber",
+    Rc::new(move |context| {
+      line.set(context.writer_info.line_number);
+      Some(false)
+    }),
+    PrintItems::new(),
+  )
+  .into()
+}
 
+fn gen_word_with_line_start_escape(word: String, line_start_escape: LineStartEscape) -> PrintItems {
   let mut items = PrintItems::new();
-  let decoration_text = match &text.kind {
-    TextDecorationKind::Emphasis => match context.configuration.emphasis_kind {
-      EmphasisKind::Asterisks => sc!("*"),
-      EmphasisKind::Underscores => {
-        if keep_asterisk(text.range.end, context) {
-          sc!("*")
-        } else {
-          sc!("_")
-        }
-      }
-    },
-    TextDecorationKind::Strong => match context.configuration.strong_kind {
-      StrongKind::Asterisks => sc!("**"),
-      StrongKind::Underscores => {
-        if keep_asterisk(text.range.end, context) {
-          sc!("**")
-        } else {
-          sc!("__")
-        }
+  let escape_index = match line_start_escape {
+    LineStartEscape::Never => None,
+    LineStartEscape::Always | LineStartEscape::IfWrapped(_) => get_line_start_escape_index(&word),
+  };
+  let Some(escape_index) = escape_index else {
+    items.push_string(word);
+    return items;
+  };
+
+  let escaped_word = format!("{}\\{}", &word[..escape_index], &word[escape_index..]);
+  match line_start_escape {
+    LineStartEscape::IfWrapped(line) => {
+      // an empty string first so that any block quote prefix is inserted before the condition
+      items.push_sc(sc!(""));
+      items.push_condition(if_true_or(
+        "escapeIfStartOfLine",
+        Rc::new(move |context| Some(context.writer_info.line_number > line.get())),
+        escaped_word.into(),
+        word.into(),
+      ));
+    }
+    _ => items.push_string(escaped_word),
+  }
+  items
+}
+
+fn gen_text_decoration(text: &TextDecoration, context: &mut Context) -> PrintItems {
+  let content_range = match (text.children.first(), text.children.last()) {
+    (Some(first), Some(last)) => first.range().start..last.range().end,
+    _ => text.range.clone(),
+  };
+  let parent = context.parent_text_decorations.last();
+  let is_only_child = parent.map(|p| p.content_range == text.range).unwrap_or(false);
+  let mut kind = match parent {
+    Some(parent) if is_only_child => parent.child_kind.unwrap_or(text.kind),
+    _ => text.kind,
+  };
+
+  // swap the nesting order of strong and emphasis when configured
+  let mut child_kind = None;
+  if kind == text.kind {
+    if let [Node::TextDecoration(child)] = text.children.as_slice() {
+      let is_strong_emphasis_pair = matches!(
+        (kind, child.kind),
+        (TextDecorationKind::Strong, TextDecorationKind::Emphasis)
+          | (TextDecorationKind::Emphasis, TextDecorationKind::Strong)
+      );
+      let should_swap = is_strong_emphasis_pair
+        && match context.configuration.strong_emphasis_order {
+          StrongEmphasisOrder::Preserve => false,
+          StrongEmphasisOrder::EmphasisOuter => kind == TextDecorationKind::Strong,
+          StrongEmphasisOrder::StrongOuter => kind == TextDecorationKind::Emphasis,
+        };
+      if should_swap {
+        child_kind = Some(kind);
+        kind = child.kind;
       }
+    }
+  }
+
+  let delimiter_char = get_delimiter_char(text, kind, &content_range, is_only_child, context);
+  let delimiter_text = match (kind, delimiter_char) {
+    (TextDecorationKind::Emphasis, '*') => sc!("*"),
+    (TextDecorationKind::Emphasis, _) => sc!("_"),
+    (TextDecorationKind::Strong, '*') => sc!("**"),
+    (TextDecorationKind::Strong, _) => sc!("__"),
+    (TextDecorationKind::Strikethrough, _) => match context.configuration.strikethrough_kind {
+      StrikethroughKind::Single if !context.configuration.subscript_superscript => sc!("~"),
+      StrikethroughKind::Single | StrikethroughKind::Double => sc!("~~"),
     },
-    TextDecorationKind::Strikethrough => sc!("~~"),
+    (TextDecorationKind::Superscript, _) => sc!("^"),
+    (TextDecorationKind::Subscript, _) => sc!("~"),
   };
 
-  items.push_sc(decoration_text);
+  let mut items = PrintItems::new();
+  items.push_sc(delimiter_text);
+  context.parent_text_decorations.push(ParentTextDecoration {
+    kind,
+    delimiter_char,
+    content_range,
+    child_kind,
+  });
   items.extend(gen_nodes(&text.children, context));
-  items.push_sc(decoration_text);
+  context.parent_text_decorations.pop();
+  items.push_sc(delimiter_text);
+  context.last_text_decoration = Some((text.range.end, delimiter_char));
 
-  items
+  return items;
+
+  /// Picks the configured delimiter when it's valid per the CommonMark flanking
+  /// rules and won't merge with a surrounding delimiter, otherwise falls back to
+  /// the other delimiter or what's in the source.
+  fn get_delimiter_char(
+    text: &TextDecoration,
+    kind: TextDecorationKind,
+    content_range: &Range,
+    is_only_child: bool,
+    context: &Context,
+  ) -> char {
+    let (configured_char, other_char) = match kind {
+      TextDecorationKind::Emphasis => match context.configuration.emphasis_kind {
+        EmphasisKind::Asterisks => ('*', '_'),
+        EmphasisKind::Underscores => ('_', '*'),
+      },
+      TextDecorationKind::Strong => match context.configuration.strong_kind {
+        StrongKind::Asterisks => ('*', '_'),
+        StrongKind::Underscores => ('_', '*'),
+      },
+      TextDecorationKind::Strikethrough | TextDecorationKind::Subscript => return '~',
+      TextDecorationKind::Superscript => return '^',
+    };
+
+    let file_text = context.file_text;
+    let before_open = file_text[..text.range.start].chars().next_back();
+    let after_open = file_text[content_range.start..].chars().next();
+    let before_close = file_text[..content_range.end].chars().next_back();
+    let after_close = file_text[text.range.end..].chars().next();
+    let parent = context.parent_text_decorations.last();
+
+    let is_valid = |c: char| {
+      let is_valid_open = is_left_flanking(before_open, after_open)
+        && (c == '*' || !is_right_flanking(before_open, after_open) || is_punctuation(before_open));
+      let is_valid_close = is_right_flanking(before_close, after_close)
+        && (c == '*' || !is_left_flanking(before_close, after_close) || is_punctuation(after_close));
+      let merges_with_parent = parent
+        .map(|parent| {
+          let touches_parent =
+            parent.content_range.start == text.range.start || parent.content_range.end == text.range.end;
+          // ex. `***text***` is well defined as emphasis wrapping strong
+          let is_combined =
+            is_only_child && parent.kind == TextDecorationKind::Emphasis && kind == TextDecorationKind::Strong;
+          touches_parent && parent.delimiter_char == c && !is_combined
+        })
+        .unwrap_or(false);
+      let merges_with_previous = context.last_text_decoration == Some((text.range.start, c));
+      is_valid_open && is_valid_close && !merges_with_parent && !merges_with_previous
+    };
+
+    if is_valid(configured_char) {
+      configured_char
+    } else if is_valid(other_char) {
+      other_char
+    } else {
+      file_text[text.range.start..].chars().next().unwrap_or(configured_char)
+    }
+  }
+
+  fn is_left_flanking(before: Option<char>, after: Option<char>) -> bool {
+    !is_whitespace(after) && (!is_punctuation(after) || is_whitespace(before) || is_punctuation(before))
+  }
+
+  fn is_right_flanking(before: Option<char>, after: Option<char>) -> bool {
+    !is_whitespace(before) && (!is_punctuation(before) || is_whitespace(after) || is_punctuation(after))
+  }
+
+  fn is_whitespace(c: Option<char>) -> bool {
+    c.map(|c| c.is_whitespace()).unwrap_or(true)
+  }
+
+  fn is_punctuation(c: Option<char>) -> bool {
+    c.map(|c| c.is_ascii_punctuation() || !c.is_ascii() && !c.is_alphanumeric() && !c.is_whitespace())
+      .unwrap_or(false)
+  }
 }
 
 fn gen_html(node: &Html, ctx: &mut Context) -> PrintItems {
-  gen_range(node.range.clone(), ctx)
+  let text = &ctx.file_text[node.range.clone()];
+  match ctx.configuration.max_blank_lines_in_html {
+    // blank lines are part of the content of elements like <pre>
+    Some(max_blank_lines) if !utils::is_raw_text_html(text) => {
+      gen_raw_text(&utils::limit_blank_lines(text.trim_end(), max_blank_lines))
+    }
+    _ => gen_range(node.range.clone(), ctx),
+  }
 }
 
 fn gen_display_math(node: &DisplayMath, ctx: &mut Context) -> PrintItems {
@@ -607,7 +1024,10 @@ fn gen_inline_math(node: &InlineMath, ctx: &mut Context) -> PrintItems {
 }
 
 fn gen_range(range: Range, ctx: &mut Context) -> PrintItems {
-  let text = ctx.file_text[range].trim_end();
+  gen_raw_text(ctx.file_text[range].trim_end())
+}
+
+fn gen_raw_text(text: &str) -> PrintItems {
   if text.is_empty() {
     return PrintItems::new();
   }
@@ -683,12 +1103,46 @@ fn gen_auto_link(link: &AutoLink, context: &mut Context) -> PrintItems {
   context.with_no_text_wrap(|context| {
     let mut items = PrintItems::new();
     items.push_sc(sc!("<"));
-    items.extend(gen_nodes(&link.children, context));
+    items.extend(context.with_no_text_normalization(|context| gen_nodes(&link.children, context)));
     items.push_sc(sc!(">"));
     ir_helpers::new_line_group(items)
   })
 }
 
+fn gen_wiki_link(wiki_link: &WikiLink, context: &mut Context) -> PrintItems {
+  let mut text = String::new();
+  if wiki_link.is_embed {
+    text.push('!');
+  }
+  text.push_str("[[");
+  push_wiki_link_part(&mut text, &wiki_link.target);
+  if let Some(heading) = &wiki_link.heading {
+    text.push('#');
+    push_wiki_link_part(&mut text, heading);
+  }
+  if let Some(alias) = &wiki_link.alias {
+    // the pipe needs to be escaped so it doesn't separate the table cell
+    text.push_str(if context.is_in_table_cell() { "\\|" } else { "|" });
+    push_wiki_link_part(&mut text, alias);
+  }
+  text.push_str("]]");
+  text.into()
+}
+
+/// Pushes the part of a wiki link with any line breaks as spaces because
+/// the link is printed as a single string.
+fn push_wiki_link_part(text: &mut String, part: &str) {
+  for (i, line) in part.lines().enumerate() {
+    if i > 0 {
+      text.push(' ');
+      // without the indentation and block quote markers of the line
+      text.push_str(line.trim_start_matches([' ', '\t', '>']).trim_end());
+    } else {
+      text.push_str(line.trim());
+    }
+  }
+}
+
 fn gen_link_reference(link_ref: &LinkReference, _: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
   items.push_string(format!("[{}]: ", link_ref.name.trim()));
@@ -699,34 +1153,47 @@ fn gen_link_reference(link_ref: &LinkReference, _: &mut Context) -> PrintItems {
   ir_helpers::new_line_group(items)
 }
 
-fn gen_inline_image(image: &InlineImage, _: &mut Context) -> PrintItems {
-  let mut items = PrintItems::new();
-  items.push_string(format!("![{}]", image.text.trim()));
-  items.push_sc(sc!("("));
-  items.push_string(image.url.trim().to_string());
-  if let Some(title) = &image.title {
-    items.push_string(format!(" \"{}\"", title.trim()));
-  }
-  items.push_sc(sc!(")"));
-  ir_helpers::new_line_group(items)
+fn gen_inline_image(image: &InlineImage, context: &mut Context) -> PrintItems {
+  context.with_no_text_wrap(|context| {
+    let mut items = PrintItems::new();
+    items.push_sc(sc!("!["));
+    items.extend(gen_nodes(&image.children, context));
+    items.push_sc(sc!("]"));
+    items.push_sc(sc!("("));
+    items.push_string(image.url.trim().to_string());
+    if let Some(title) = &image.title {
+      items.push_string(format!(" \"{}\"", title.trim()));
+    }
+    items.push_sc(sc!(")"));
+    ir_helpers::new_line_group(items)
+  })
 }
 
-fn gen_reference_image(image: &ReferenceImage, _: &mut Context) -> PrintItems {
-  let mut items = PrintItems::new();
-  items.push_string(format!("![{}]", image.text.trim()));
-  items.push_string(format!("[{}]", image.reference.trim()));
-  ir_helpers::new_line_group(items)
+fn gen_reference_image(image: &ReferenceImage, context: &mut Context) -> PrintItems {
+  context.with_no_text_wrap(|context| {
+    let mut items = PrintItems::new();
+    items.push_sc(sc!("!["));
+    items.extend(gen_nodes(&image.children, context));
+    items.push_sc(sc!("]"));
+    items.push_string(format!("[{}]", image.reference.trim()));
+    ir_helpers::new_line_group(items)
+  })
 }
 
 fn gen_list(list: &List, is_alternate: bool, context: &mut Context) -> PrintItems {
-  context.mark_in_list(|context| {
+  let list_char = get_unordered_list_char(list, is_alternate, context);
+  let is_loose = get_is_loose_list(list, context);
+  let parent_is_loose = std::mem::replace(&mut context.is_loose_list, is_loose);
+  let items = context.mark_in_list(|context| {
     let mut items = PrintItems::new();
 
     // generate items
-    for (index, child) in list.children.iter().enumerate() {
+    for (index, child) in get_list_items_in_order(list, context).into_iter().enumerate() {
       if index > 0 {
         items.push_signal(Signal::NewLine);
-        if utils::has_leading_blankline(child.range().start, context.file_text) {
+        // the blank lines stay at the same positions when the items are moved
+        let source_child = &list.children[index];
+        if is_loose.unwrap_or_else(|| utils::has_leading_blankline(source_child.range().start, context.file_text)) {
           items.push_signal(Signal::NewLine);
         }
       }
@@ -739,7 +1206,7 @@ fn gen_list(list: &List, is_alternate: bool, context: &mut Context) -> PrintItem
         };
         format!("{}{}", display_index, end_char)
       } else {
-        String::from(context.configuration.unordered_list_kind.list_char(is_alternate))
+        String::from(list_char)
       };
       let indent_increment = (prefix_text.chars().count() + 1) as u32;
       context.indent_level += indent_increment;
@@ -756,9 +1223,89 @@ fn gen_list(list: &List, is_alternate: bool, context: &mut Context) -> PrintItem
     }
 
     items
+  });
+  context.is_loose_list = parent_is_loose;
+  if list.start_index.is_none() {
+    context.last_list_char = Some((list.range.end, list_char));
+  }
+  items
+}
+
+fn get_unordered_list_char(list: &List, is_alternate: bool, context: &Context) -> char {
+  let has_task_items = list.children.iter().any(|child| get_task_list_marker(child).is_some());
+  let list_kind = match context.configuration.task_list_kind {
+    Some(task_list_kind) if has_task_items => task_list_kind,
+    _ => context.configuration.unordered_list_kind,
+  };
+  let list_char = list_kind.list_char(is_alternate);
+  // a list directly after a list with the same character would be merged into it, which
+  // can happen when task lists use a different kind
+  match context.last_list_char {
+    Some((end, last_char))
+      if context.configuration.task_list_kind.is_some()
+        && last_char == list_char
+        && context
+          .file_text
+          .get(end..list.range.start)
+          .is_some_and(|text| text.trim().is_empty()) =>
+    {
+      list_kind.list_char(!is_alternate)
+    }
+    _ => list_char,
+  }
+}
+
+/// Gets the items of the list in the order they're generated.
+fn get_list_items_in_order<'a>(list: &'a List, context: &Context) -> Vec<&'a Node> {
+  let mut children = list.children.iter().collect::<Vec<_>>();
+  if context.configuration.completed_task_order == CompletedTaskOrder::Last {
+    // stable, so the items keep their order otherwise
+    children.sort_by_key(|child| is_completed_task(child));
+  }
+  children
+}
+
+fn is_completed_task(node: &Node) -> bool {
+  get_task_list_marker(node).is_some_and(|marker| marker.is_checked)
+}
+
+/// Gets the task list marker of a list item, which is on the item's paragraph
+/// in loose lists.
+fn get_task_list_marker(node: &Node) -> Option<&TaskListMarker> {
+  let Node::Item(item) = node else {
+    return None;
+  };
+  item.marker.as_ref().or_else(|| match item.children.first() {
+    Some(Node::Paragraph(paragraph)) => paragraph.marker.as_ref(),
+    _ => None,
   })
 }
 
+/// Gets if the items of the list should be separated by blank lines or `None`
+/// to keep the blank lines from the source.
+fn get_is_loose_list(list: &List, context: &Context) -> Option<bool> {
+  match context.configuration.list_spacing {
+    ListSpacing::Preserve => None,
+    ListSpacing::Tight => Some(false),
+    ListSpacing::Loose => Some(true),
+    ListSpacing::Consistent => {
+      let gaps = list
+        .children
+        .iter()
+        .skip(1)
+        .map(|child| utils::has_leading_blankline(child.range().start, context.file_text))
+        .collect::<Vec<_>>();
+      let loose_count = gaps.iter().filter(|is_loose| **is_loose).count();
+      let tight_count = gaps.len() - loose_count;
+      match loose_count.cmp(&tight_count) {
+        std::cmp::Ordering::Greater => Some(true),
+        std::cmp::Ordering::Less => Some(false),
+        std::cmp::Ordering::Equal => gaps.first().copied(),
+      }
+    }
+  }
+}
+
 fn gen_item(item: &Item, context: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
 
@@ -777,7 +1324,10 @@ fn gen_item(item: &Item, context: &mut Context) -> PrintItems {
 
   if !item.sub_lists.is_empty() {
     items.push_signal(Signal::NewLine);
-    if utils::has_leading_blankline(item.sub_lists.first().unwrap().range().start, context.file_text) {
+    // a blank line before a nested list makes the list loose
+    if context.is_loose_list != Some(false)
+      && utils::has_leading_blankline(item.sub_lists.first().unwrap().range().start, context.file_text)
+    {
       items.push_signal(Signal::NewLine);
     }
     items.extend(gen_nodes(&item.sub_lists, context));
@@ -813,18 +1363,26 @@ fn gen_task_list_marker_children(
   // insert the remaining children without indent
   if indent_child_index_end > 0 && indent_child_index_end != children.len() {
     items.push_signal(Signal::NewLine);
-    if utils::has_leading_blankline(children[indent_child_index_end].range().start, context.file_text) {
-      items.push_signal(Signal::NewLine);
+    let child = &children[indent_child_index_end];
+    if utils::has_leading_blankline(child.range().start, context.file_text) {
+      for _ in 0..get_blank_line_count(child, context) {
+        items.push_signal(Signal::NewLine);
+      }
     }
   }
   items.extend(gen_nodes(&children[indent_child_index_end..], context));
   items
 }
 
-fn gen_task_list_marker(marker: &TaskListMarker, _: &mut Context) -> PrintItems {
+fn gen_task_list_marker(marker: &TaskListMarker, context: &mut Context) -> PrintItems {
   let mut items = PrintItems::new();
   if marker.is_checked {
-    items.push_string("[x]".into());
+    let is_uppercase = match context.configuration.task_list_marker_case {
+      TaskListMarkerCase::Lowercase => false,
+      TaskListMarkerCase::Uppercase => true,
+      TaskListMarkerCase::Preserve => context.file_text[marker.range.clone()].contains('X'),
+    };
+    items.push_string(if is_uppercase { "[X]" } else { "[x]" }.into());
   } else {
     items.push_string("[ ]".into());
   }
@@ -1020,7 +1578,7 @@ fn gen_table(table: &Table, context: &mut Context) -> PrintItems {
 }
 
 fn gen_table_cell(table_cell: &TableCell, context: &mut Context) -> PrintItems {
-  gen_nodes(&table_cell.children, context)
+  context.mark_in_table_cell(|context| gen_nodes(&table_cell.children, context))
 }
 
 fn gen_metadata_block(node: &MetadataBlock, context: &mut Context) -> PrintItems {
@@ -1099,15 +1657,17 @@ fn measure_longest_line_width(items: PrintItems, max_width: u32) -> usize {
 }
 
 fn get_space_or_newline_based_on_config(context: &Context) -> PrintItems {
-  if context.is_text_wrap_disabled() {
-    return space();
-  }
-  match context.configuration.text_wrap {
-    TextWrap::Always => Signal::SpaceOrNewLine.into(),
-    TextWrap::Never | TextWrap::Maintain => space(),
+  if is_space_or_newline_wrapping(context) {
+    Signal::SpaceOrNewLine.into()
+  } else {
+    space()
   }
 }
 
+fn is_space_or_newline_wrapping(context: &Context) -> bool {
+  !context.is_text_wrap_disabled() && context.configuration.text_wrap == TextWrap::Always
+}
+
 fn space() -> PrintItems {
   let mut items = PrintItems::new();
   items.push_space();
@@ -0,0 +1,149 @@
+use super::common::*;
+use crate::configuration::HeadingCase;
+
+/// Words that stay lowercase in title case unless they're the first or last word.
+const TITLE_CASE_MINOR_WORDS: [&str; 23] = [
+  "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on", "or", "over", "per", "so",
+  "the", "to", "via", "vs", "with",
+];
+
+/// Changes the case of the words in the text of headings.
+///
+/// Code, links, and images are left as-is along with words that are in the
+/// exceptions or have a capital letter after their first letter (ex. `API` or `iOS`).
+pub fn apply_heading_case(nodes: &mut [Node], case: HeadingCase, exceptions: &[String]) {
+  if case == HeadingCase::Preserve {
+    return;
+  }
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::Heading(heading) = node {
+      apply_heading_case_to_children(&mut heading.children, case, exceptions);
+    }
+  });
+}
+
+enum Word {
+  Text {
+    text_index: usize,
+    range: Range,
+  },
+  /// A node that isn't changed (ex. code or a link).
+  Other,
+}
+
+fn apply_heading_case_to_children(children: &mut [Node], case: HeadingCase, exceptions: &[String]) {
+  let mut texts = Vec::new();
+  let mut words = Vec::new();
+  collect_words(children, &mut texts, &mut words);
+
+  let last_index = words.len().saturating_sub(1);
+  let mut is_sentence_start = true;
+  for (index, word) in words.into_iter().enumerate() {
+    let Word::Text { text_index, range } = word else {
+      is_sentence_start = false;
+      continue;
+    };
+    let text = &mut texts[text_index];
+    let is_first_word = is_sentence_start;
+    is_sentence_start = is_sentence_end(&text.text[range.clone()]);
+    let Some((letter_index, letter)) = get_new_first_letter(
+      &text.text[range.clone()],
+      is_first_word,
+      index,
+      last_index,
+      case,
+      exceptions,
+    ) else {
+      continue;
+    };
+    let letter_index = range.start + letter_index;
+    let letter_pos = text.range.start + letter_index;
+    let is_in_reference = text
+      .character_references
+      .iter()
+      .any(|reference| reference.range.contains(&letter_pos));
+    let old_letter = text.text[letter_index..].chars().next().unwrap();
+    // keep the byte length the same so the ranges within the text stay valid
+    if !is_in_reference && old_letter.len_utf8() == letter.len_utf8() {
+      text.text.replace_range(
+        letter_index..letter_index + old_letter.len_utf8(),
+        letter.encode_utf8(&mut [0; 4]),
+      );
+    }
+  }
+}
+
+fn collect_words<'a>(nodes: &'a mut [Node], texts: &mut Vec<&'a mut Text>, words: &mut Vec<Word>) {
+  for node in nodes {
+    match node {
+      Node::Text(text) => {
+        let text_index = texts.len();
+        let mut word_start = None;
+        for (index, c) in text.text.char_indices().chain(std::iter::once((text.text.len(), ' '))) {
+          match (word_start, c.is_whitespace()) {
+            (None, false) => word_start = Some(index),
+            (Some(start), true) => {
+              words.push(Word::Text {
+                text_index,
+                range: start..index,
+              });
+              word_start = None;
+            }
+            _ => {}
+          }
+        }
+        texts.push(text);
+      }
+      Node::TextDecoration(decoration) => collect_words(&mut decoration.children, texts, words),
+      Node::SoftBreak(_) | Node::HardBreak(_) => {}
+      _ => words.push(Word::Other),
+    }
+  }
+}
+
+/// Gets if the word ends a sentence or clause so the next word starts a new one
+/// (ex. `Ready?` or `Note:`).
+fn is_sentence_end(word: &str) -> bool {
+  word
+    .trim_end_matches(['"', '\'', '”', '’', ')'])
+    .ends_with(['.', '?', '!', ':'])
+}
+
+/// Gets the index and new value of the word's first letter when it should change.
+fn get_new_first_letter(
+  word: &str,
+  is_first_word: bool,
+  index: usize,
+  last_index: usize,
+  case: HeadingCase,
+  exceptions: &[String],
+) -> Option<(usize, char)> {
+  let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+  let is_exception = exceptions.iter().any(|exception| exception.eq_ignore_ascii_case(core));
+  let has_inner_capital = core.chars().skip(1).any(char::is_uppercase);
+  if is_exception || has_inner_capital || core == "I" || core.starts_with("I'") || core.starts_with("I’") {
+    return None;
+  }
+
+  let is_upper = match case {
+    HeadingCase::Preserve => return None,
+    HeadingCase::SentenceCase => is_first_word,
+    HeadingCase::TitleCase => {
+      is_first_word || index == last_index || !TITLE_CASE_MINOR_WORDS.contains(&core.to_lowercase().as_str())
+    }
+  };
+  // words starting with a number (ex. `2nd`) are left as-is
+  let (letter_index, letter) = word
+    .char_indices()
+    .find(|(_, c)| c.is_alphanumeric())
+    .filter(|(_, c)| c.is_alphabetic())?;
+  let mut new_letters = if is_upper {
+    letter.to_uppercase().collect::<Vec<_>>()
+  } else {
+    letter.to_lowercase().collect::<Vec<_>>()
+  };
+  match (new_letters.pop(), new_letters.is_empty()) {
+    (Some(new_letter), true) if new_letter != letter => Some((letter_index, new_letter)),
+    _ => None,
+  }
+}
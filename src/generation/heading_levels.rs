@@ -0,0 +1,51 @@
+use super::common::*;
+
+/// A heading whose level changes when normalizing the heading levels.
+pub struct HeadingLevelChange {
+  pub range: Range,
+  pub old_level: u32,
+  pub new_level: u32,
+}
+
+/// Shifts the heading levels so the top headings are at the start level and
+/// removes skipped levels (ex. a `###` directly under a `#` becomes `##`).
+///
+/// Headings in ignored top-level nodes keep their level, but the headings after
+/// them are still nested under them.
+pub fn normalize_heading_levels(
+  nodes: &mut [Node],
+  start_level: u32,
+  is_ignored: impl Fn(usize) -> bool,
+) -> Vec<HeadingLevelChange> {
+  // the written and normalized levels of the headings the current heading is under
+  let mut parent_levels: Vec<(u32, u32)> = Vec::new();
+  let mut changes = Vec::new();
+  for (i, node) in nodes.iter_mut().enumerate() {
+    let is_ignored = is_ignored(i);
+    visit_nodes_mut(std::slice::from_mut(node), &mut |node| {
+      if let Node::Heading(heading) = node {
+        while parent_levels.last().is_some_and(|(level, _)| *level >= heading.level) {
+          parent_levels.pop();
+        }
+        let new_level = if is_ignored {
+          heading.level
+        } else {
+          match parent_levels.last() {
+            Some((_, parent_level)) => std::cmp::min(parent_level + 1, 6),
+            None => start_level,
+          }
+        };
+        parent_levels.push((heading.level, new_level));
+        if new_level != heading.level {
+          changes.push(HeadingLevelChange {
+            range: heading.range.clone(),
+            old_level: heading.level,
+            new_level,
+          });
+          heading.level = new_level;
+        }
+      }
+    });
+  }
+  changes
+}
@@ -0,0 +1,485 @@
+use super::strip_metadata_header;
+
+/// Masks the MDX specific syntax in the text so that it's parsed as HTML.
+///
+/// ESM statements along with JSX elements and expressions on their own lines are
+/// masked as HTML comment blocks, while inline JSX elements and expressions, which
+/// includes those continuing a paragraph, are masked as inline HTML tags. The masked
+/// text has the same length as the original so the ranges of the parsed nodes can be
+/// used to print the original text.
+pub fn mask_mdx_syntax(text: &str) -> String {
+  let bytes = text.as_bytes();
+  let mut masked = bytes.to_vec();
+  let mut pos = text.len() - strip_metadata_header(text).len();
+  let mut fence: Option<&[u8]> = None;
+  let mut previous_line_blank = true;
+  let mut previous_line_text = false;
+
+  while pos < bytes.len() {
+    let line_end = find_line_end(bytes, pos);
+    let line = &text[pos..line_end];
+    let content_start = get_line_content_start(line);
+    let content = &line[content_start..];
+    let is_blank = line.trim().is_empty();
+
+    // skip code blocks
+    if let Some(fence_text) = fence {
+      if content.as_bytes().starts_with(fence_text)
+        && content.trim_start_matches(fence_text[0] as char).trim().is_empty()
+      {
+        fence = None;
+      }
+      pos = next_line_start(bytes, line_end);
+      previous_line_blank = is_blank;
+      previous_line_text = false;
+      continue;
+    }
+    if let Some(fence_text) = get_fence_text(content) {
+      fence = Some(&bytes[pos + content_start..pos + content_start + fence_text.len()]);
+      pos = next_line_start(bytes, line_end);
+      previous_line_blank = false;
+      previous_line_text = false;
+      continue;
+    }
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent >= 4 && previous_line_blank && !is_blank {
+      // possibly an indented code block, so leave it alone
+      pos = next_line_start(bytes, line_end);
+      continue;
+    }
+
+    if indent == 0 && previous_line_blank && is_esm_start(line) {
+      // ESM ends at a blank line
+      let mut end = line_end;
+      while end < bytes.len() {
+        let next_line_end = find_line_end(bytes, next_line_start(bytes, end));
+        if text[end..next_line_end].trim().is_empty() {
+          break;
+        }
+        end = next_line_end;
+      }
+      mask_block(&mut masked, text, pos..end);
+      pos = next_line_start(bytes, end);
+      previous_line_blank = false;
+      previous_line_text = false;
+      continue;
+    }
+
+    if content_start == indent && indent <= 3 {
+      let flow_end = match content.as_bytes().first() {
+        Some(b'<') => get_jsx_flow_end(text, pos + indent).map(|end| get_jsx_children_end(text, end, indent)),
+        Some(b'{') if !previous_line_text => get_expression_flow_end(text, pos + indent),
+        _ => None,
+      };
+      if let Some(end) = flow_end {
+        if previous_line_text {
+          // JSX can't interrupt a paragraph, so it's masked as inline
+          let end = pos + indent + text[pos + indent..end].trim_end().len();
+          mask_inline_range(&mut masked, text, pos + indent..end);
+        } else {
+          mask_block(&mut masked, text, pos + indent..end);
+          previous_line_text = false;
+        }
+        pos = next_line_start(bytes, end);
+        previous_line_blank = false;
+        continue;
+      }
+    }
+
+    pos = mask_inline(&mut masked, text, pos + content_start, line_end);
+    previous_line_blank = is_blank;
+    // headings end on the line, so anything after isn't a continuation
+    previous_line_text = !is_blank && !content.starts_with('#');
+  }
+
+  String::from_utf8(masked).unwrap()
+}
+
+/// Masks the inline JSX and expressions starting at the position and returns
+/// the start of the next line to scan.
+fn mask_inline(masked: &mut [u8], text: &str, start: usize, line_end: usize) -> usize {
+  let bytes = text.as_bytes();
+  let mut pos = start;
+  let mut line_end = line_end;
+  while pos < line_end {
+    let end = match bytes[pos] {
+      b'\\' => Some(pos + 2),
+      b'`' => Some(skip_code_span(bytes, pos)),
+      b']' if bytes.get(pos + 1) == Some(&b'(') => Some(skip_balanced(bytes, pos + 1, b'(', b')').unwrap_or(pos + 2)),
+      b'{' => {
+        let end = skip_expression(bytes, pos);
+        if let Some(end) = end {
+          mask_inline_range(masked, text, pos..end);
+        }
+        Some(end.unwrap_or(pos + 1))
+      }
+      b'<' => {
+        let end = skip_jsx_tag(bytes, pos);
+        if let Some(end) = end {
+          mask_inline_range(masked, text, pos..end);
+        }
+        Some(end.unwrap_or(pos + 1))
+      }
+      _ => None,
+    };
+    pos = match end {
+      Some(end) => std::cmp::min(end, bytes.len()),
+      None => pos + 1,
+    };
+    if pos > line_end {
+      // continue scanning the line the construct ended on
+      line_end = find_line_end(bytes, pos);
+    }
+  }
+  next_line_start(bytes, line_end)
+}
+
+/// Gets the end of the line that a JSX element on its own line ends on.
+fn get_jsx_flow_end(text: &str, start: usize) -> Option<usize> {
+  let bytes = text.as_bytes();
+  let mut pos = start;
+  loop {
+    pos = skip_jsx_tag(bytes, pos)?;
+    let line_end = find_line_end(bytes, pos);
+    let rest = text[pos..line_end].trim();
+    if rest.is_empty() {
+      return Some(line_end);
+    } else if rest.starts_with('<') {
+      // ex. `<Tab></Tab>`
+      pos += text[pos..].len() - text[pos..].trim_start().len();
+    } else {
+      return None;
+    }
+  }
+}
+
+/// Gets the end of the lines after a JSX element on its own line that are indented
+/// more than it, which are kept as-is so the children keep their indentation.
+fn get_jsx_children_end(text: &str, end: usize, indent: usize) -> usize {
+  let bytes = text.as_bytes();
+  let mut end = end;
+  while end < bytes.len() {
+    let next_line_end = find_line_end(bytes, next_line_start(bytes, end));
+    let line = &text[next_line_start(bytes, end)..next_line_end];
+    if line.trim().is_empty() || line.len() - line.trim_start_matches(' ').len() <= indent {
+      break;
+    }
+    end = next_line_end;
+  }
+  end
+}
+
+/// Gets the end of the line that an expression on its own line ends on.
+fn get_expression_flow_end(text: &str, start: usize) -> Option<usize> {
+  let bytes = text.as_bytes();
+  let end = skip_expression(bytes, start)?;
+  let line_end = find_line_end(bytes, end);
+  text[end..line_end].trim().is_empty().then_some(line_end)
+}
+
+/// Skips over a JSX opening, closing, or self-closing tag.
+fn skip_jsx_tag(bytes: &[u8], start: usize) -> Option<usize> {
+  let mut pos = start + 1;
+  if bytes.get(pos) == Some(&b'/') {
+    pos += 1;
+  }
+  if bytes.get(pos) == Some(&b'>') {
+    // fragment
+    return Some(pos + 1);
+  }
+  if !bytes.get(pos)?.is_ascii_alphabetic() {
+    return None;
+  }
+  while bytes
+    .get(pos)
+    .map(|b| is_jsx_name_byte(*b) || *b == b'.')
+    .unwrap_or(false)
+  {
+    pos += 1;
+  }
+
+  loop {
+    let whitespace_start = pos;
+    while bytes.get(pos)?.is_ascii_whitespace() {
+      pos += 1;
+    }
+    match *bytes.get(pos)? {
+      b'>' => return Some(pos + 1),
+      b'/' if bytes.get(pos + 1) == Some(&b'>') => return Some(pos + 2),
+      b'{' if pos > whitespace_start => pos = skip_expression(bytes, pos)?,
+      b if is_jsx_name_byte(b) && pos > whitespace_start => {
+        while bytes.get(pos).map(|b| is_jsx_name_byte(*b)).unwrap_or(false) {
+          pos += 1;
+        }
+        if bytes.get(pos) == Some(&b'=') {
+          pos += 1;
+          pos = match *bytes.get(pos)? {
+            quote @ (b'"' | b'\'') => skip_string(bytes, pos, quote)?,
+            b'{' => skip_expression(bytes, pos)?,
+            _ => return None,
+          };
+        }
+      }
+      _ => return None,
+    }
+  }
+}
+
+fn is_jsx_name_byte(b: u8) -> bool {
+  b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b':' | b'$')
+}
+
+/// Skips over an expression in braces, accounting for nested braces and strings.
+fn skip_expression(bytes: &[u8], start: usize) -> Option<usize> {
+  let mut depth = 0;
+  let mut pos = start;
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'{' => depth += 1,
+      b'}' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(pos + 1);
+        }
+      }
+      quote @ (b'"' | b'\'' | b'`') => {
+        pos = skip_string(bytes, pos, quote)?;
+        continue;
+      }
+      b'\n'
+        if bytes[pos + 1..]
+          .iter()
+          .take_while(|b| **b != b'\n')
+          .all(|b| b.is_ascii_whitespace()) =>
+      {
+        // an expression can't span a blank line
+        return None;
+      }
+      _ => {}
+    }
+    pos += 1;
+  }
+  None
+}
+
+fn skip_string(bytes: &[u8], start: usize, quote: u8) -> Option<usize> {
+  let mut pos = start + 1;
+  while pos < bytes.len() {
+    match bytes[pos] {
+      b'\\' => pos += 1,
+      b if b == quote => return Some(pos + 1),
+      b'\n' if quote != b'`' => return None,
+      _ => {}
+    }
+    pos += 1;
+  }
+  None
+}
+
+fn skip_balanced(bytes: &[u8], start: usize, open: u8, close: u8) -> Option<usize> {
+  let mut depth = 0;
+  for (i, b) in bytes.iter().enumerate().skip(start) {
+    if *b == open {
+      depth += 1;
+    } else if *b == close {
+      depth -= 1;
+      if depth == 0 {
+        return Some(i + 1);
+      }
+    } else if *b == b'\n' {
+      return None;
+    }
+  }
+  None
+}
+
+fn skip_code_span(bytes: &[u8], start: usize) -> usize {
+  let count = bytes[start..].iter().take_while(|b| **b == b'`').count();
+  let mut pos = start + count;
+  while pos < bytes.len() {
+    if bytes[pos] == b'`' {
+      let end_count = bytes[pos..].iter().take_while(|b| **b == b'`').count();
+      if end_count == count {
+        return pos + end_count;
+      }
+      pos += end_count;
+    } else {
+      pos += 1;
+    }
+  }
+  start + count
+}
+
+/// Masks the lines as an HTML comment block or, when too short, as a processing
+/// instruction block (ex. `<?x?>`). Both end on the last masked line, unlike other
+/// HTML blocks that would continue until a blank line.
+fn mask_block(masked: &mut [u8], text: &str, range: std::ops::Range<usize>) {
+  let block_text = text[range.clone()].trim_end();
+  let end = range.start + block_text.len();
+  if end - range.start < 3 {
+    return;
+  }
+  // join the lines when the first or last is too short for the markers (ex. `{\n  a\n}`)
+  let first_line_len = block_text.find('\n').unwrap_or(block_text.len());
+  let last_line_len = block_text.len() - block_text.rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let is_join_lines = first_line_len < 4 || last_line_len < 3;
+  // keep the indentation so the lines stay in their container (ex. a list item)
+  for b in masked[range.start..end].iter_mut() {
+    *b = match *b {
+      b'\n' | b'\r' if is_join_lines => b' ',
+      b'\n' | b'\r' | b' ' | b'\t' => *b,
+      _ => b'x',
+    };
+  }
+  if end - range.start >= 7 {
+    masked[range.start..range.start + 4].copy_from_slice(b"<!--");
+    masked[end - 3..end].copy_from_slice(b"-->");
+  } else {
+    masked[range.start..range.start + 2].copy_from_slice(b"<?");
+    masked[end - 2..end].copy_from_slice(b"?>");
+  }
+}
+
+/// Masks the range as an inline HTML tag on a single line (ex. `<xxxxx>`), so
+/// none of its lines can start a block (ex. a `}` line masked as `>`).
+fn mask_inline_range(masked: &mut [u8], text: &str, range: std::ops::Range<usize>) {
+  if range.len() < 3 {
+    return;
+  }
+  for (i, b) in text.as_bytes()[range.clone()].iter().enumerate() {
+    masked[range.start + i] = match b {
+      b'\n' | b'\r' => b' ',
+      b' ' | b'\t' => *b,
+      _ => b'x',
+    };
+  }
+  masked[range.start] = b'<';
+  masked[range.end - 1] = b'>';
+  // the tag name must directly follow the `<` (ex. `{ a }`)
+  if matches!(masked[range.start + 1], b' ' | b'\t') {
+    masked[range.start + 1] = b'x';
+  }
+}
+
+fn is_esm_start(line: &str) -> bool {
+  ["import", "export"].iter().any(|keyword| {
+    line
+      .strip_prefix(keyword)
+      .and_then(|rest| rest.chars().next())
+      .map(|c| c.is_whitespace() || c == '{' || c == '*')
+      .unwrap_or(false)
+  })
+}
+
+fn get_fence_text(content: &str) -> Option<&str> {
+  let first_char = content.chars().next().filter(|c| *c == '`' || *c == '~')?;
+  let count = content.chars().take_while(|c| *c == first_char).count();
+  if count >= 3 {
+    Some(&content[..count])
+  } else {
+    None
+  }
+}
+
+/// Gets the start of the line's content after any indentation, block quote
+/// markers, and list markers.
+fn get_line_content_start(line: &str) -> usize {
+  let mut start = 0;
+  loop {
+    let rest = &line[start..];
+    let trimmed = rest.trim_start_matches([' ', '\t', '>']);
+    let word = trimmed.split_whitespace().next().unwrap_or("");
+    let is_list_marker = super::is_list_word(word) && trimmed[word.len()..].starts_with([' ', '\t']);
+    if is_list_marker {
+      start += rest.len() - trimmed.len() + word.len();
+    } else {
+      return start + rest.len() - trimmed.len();
+    }
+  }
+}
+
+fn find_line_end(bytes: &[u8], pos: usize) -> usize {
+  bytes[pos..]
+    .iter()
+    .position(|b| *b == b'\n')
+    .map(|i| pos + i)
+    .unwrap_or(bytes.len())
+}
+
+fn next_line_start(bytes: &[u8], line_end: usize) -> usize {
+  std::cmp::min(line_end + 1, bytes.len())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn masks_esm() {
+    assert_eq!(
+      mask_mdx_syntax("import A from 'a';\nimport {\n  B,\n} from 'b';\n\nexport const c = 1;\n\nimporting\n"),
+      "<!--xx x xxxx xxxx\nxxxxxx x\n  xx\nx xxxx x-->\n\n<!--xx xxxxx x x-->\n\nimporting\n"
+    );
+  }
+
+  #[test]
+  fn masks_jsx_flow() {
+    assert_eq!(
+      mask_mdx_syntax("<Tabs\n  items={['a', 'b']}>\n\n## Heading\n\n</Tabs>\n<A/>\n"),
+      "<!--x\n  xxxxxxxxxxxx xxx-->\n\n## Heading\n\n<!---->\n<??>\n"
+    );
+  }
+
+  #[test]
+  fn masks_short_jsx_flow_without_continuing_the_block() {
+    assert_eq!(mask_mdx_syntax("<A/>\n*  a\n\n<Bc>\n"), "<??>\n*  a\n\n<??>\n");
+  }
+
+  #[test]
+  fn masks_jsx_continuing_a_paragraph_as_inline() {
+    assert_eq!(mask_mdx_syntax("A paragraph\n<Foo />\n"), "A paragraph\n<xxx x>\n");
+    assert_eq!(
+      mask_mdx_syntax("A paragraph\n<Foo>\n  <Bar />\n</Foo>\n"),
+      "A paragraph\n<xxxx   xxxx x>\n<xxxx>\n"
+    );
+    assert_eq!(mask_mdx_syntax("# Title\n<Foo />\n"), "# Title\n<!---->\n");
+  }
+
+  #[test]
+  fn masks_jsx_children_indented_more_than_the_element() {
+    assert_eq!(
+      mask_mdx_syntax("<Foo>\n  text\n</Foo>\n\n  <A />\n"),
+      "<!--x\n  x-->\n<?xx?>\n\n  <? ?>\n"
+    );
+  }
+
+  #[test]
+  fn masks_multi_line_expressions() {
+    assert_eq!(
+      mask_mdx_syntax("Text with {\n  multi\n} expression.\n"),
+      "Text with <x  xxxxx > expression.\n"
+    );
+    assert_eq!(mask_mdx_syntax("{\n  a\n}\n"), "<!---->\n");
+  }
+
+  #[test]
+  fn masks_inline() {
+    assert_eq!(
+      mask_mdx_syntax("Some {a*b*c} and <Badge count={1} /> `{a}` \\{a} [a](b{c}).\n"),
+      "Some <xxxxx> and <xxxxx xxxxxxxxx x> `{a}` \\{a} [a](b{c}).\n"
+    );
+    assert_eq!(mask_mdx_syntax("Some { a } text\n"), "Some <xx > text\n");
+  }
+
+  #[test]
+  fn skips_code_blocks() {
+    let text = "```js\n{a}\n<A />\n```\n\n    {a}\n\n---\n";
+    assert_eq!(mask_mdx_syntax(text), text);
+  }
+
+  #[test]
+  fn skips_metadata_header() {
+    let text = "---\ntitle: {a}\n---\n\n{a}\n";
+    assert_eq!(mask_mdx_syntax(text), "---\ntitle: {a}\n---\n\n<?>\n");
+  }
+}
@@ -1,12 +1,31 @@
+mod character_references;
 mod cmark;
 pub mod common;
+mod escapes;
+mod footnotes;
 mod gen_types;
 mod generate;
+mod heading_case;
+mod heading_levels;
+mod mdx;
 mod metadata;
+mod plain_text;
+mod punctuation;
+mod references;
+mod slugs;
+mod toc;
 mod utils;
 
 pub use cmark::*;
+pub use footnotes::*;
 pub use gen_types::*;
 pub use generate::*;
+pub use heading_case::*;
+pub use heading_levels::*;
+pub use mdx::*;
 pub use metadata::*;
+pub use plain_text::*;
+pub use references::*;
+pub use slugs::*;
+pub use toc::*;
 pub use utils::*;
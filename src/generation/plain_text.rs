@@ -0,0 +1,82 @@
+use super::common::*;
+
+/// Gets the text of the nodes without markup, escapes, or character references
+/// (ex. `Some code & more` for `*Some* `code` &amp; more`).
+pub fn get_plain_text(nodes: &[Node], file_text: &str) -> String {
+  let mut result = String::new();
+  push_plain_text(&mut result, nodes, file_text);
+  result
+}
+
+fn push_plain_text(result: &mut String, nodes: &[Node], file_text: &str) {
+  for node in nodes {
+    // whitespace between nodes isn't included in the text nodes
+    let start = node.range().start;
+    let has_preceding_whitespace = file_text[..start].ends_with(char::is_whitespace);
+    if has_preceding_whitespace && !result.is_empty() && !result.ends_with(' ') {
+      result.push(' ');
+    }
+
+    match node {
+      Node::Text(text) => push_text(result, text),
+      Node::Code(code) => result.push_str(&code.code),
+      Node::TextDecoration(node) => push_plain_text(result, &node.children, file_text),
+      Node::InlineLink(node) => push_plain_text(result, &node.children, file_text),
+      Node::ReferenceLink(node) => push_plain_text(result, &node.children, file_text),
+      Node::ShortcutLink(node) => push_plain_text(result, &node.children, file_text),
+      Node::AutoLink(node) => push_plain_text(result, &node.children, file_text),
+      Node::InlineImage(node) => push_plain_text(result, &node.children, file_text),
+      Node::ReferenceImage(node) => push_plain_text(result, &node.children, file_text),
+      Node::WikiLink(link) => result.push_str(link.alias.as_ref().unwrap_or(&link.target)),
+      Node::SoftBreak(_) | Node::HardBreak(_) => {
+        if !result.ends_with(' ') {
+          result.push(' ');
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+fn push_text(result: &mut String, text: &Text) {
+  let mut last_end = 0;
+  for reference in &text.character_references {
+    let start = reference.range.start - text.range.start;
+    let end = reference.range.end - text.range.start;
+    if let Some(before) = text.text.get(last_end..start) {
+      push_unescaped(result, before);
+      result.push_str(&reference.value);
+      last_end = end;
+    }
+  }
+  push_unescaped(result, text.text.get(last_end..).unwrap_or_default());
+}
+
+fn push_unescaped(result: &mut String, text: &str) {
+  let mut chars = text.chars().peekable();
+  while let Some(c) = chars.next() {
+    match chars.peek() {
+      Some(next) if c == '\\' && next.is_ascii_punctuation() => result.push(chars.next().unwrap()),
+      _ => result.push(c),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::configuration::ConfigurationBuilder;
+  use crate::generation::parse_cmark_ast;
+
+  #[test]
+  fn it_should_get_plain_text() {
+    let file_text = "# *Some* `code` &amp; \\*more\\* [link](url)!\n";
+    let source_file = parse_cmark_ast(file_text, &ConfigurationBuilder::new().build())
+      .ok()
+      .unwrap();
+    let Node::Heading(heading) = &source_file.children[0] else {
+      unreachable!();
+    };
+    assert_eq!(get_plain_text(&heading.children, file_text), "Some code & *more* link!");
+  }
+}
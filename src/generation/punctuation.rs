@@ -0,0 +1,33 @@
+use std::borrow::Cow;
+
+/// Writes typographic quotes, dashes, and ellipses as ASCII characters.
+pub fn to_ascii_punctuation(text: &str) -> Cow<'_, str> {
+  if text.is_ascii() {
+    return Cow::Borrowed(text);
+  }
+
+  let mut result = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '“' | '”' | '„' => result.push('"'),
+      '‘' | '’' | '‚' => result.push('\''),
+      '–' => result.push_str("--"),
+      '—' => result.push_str("---"),
+      '…' => result.push_str("..."),
+      _ => result.push(c),
+    }
+  }
+  Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn it_should_convert_to_ascii_punctuation() {
+    assert_eq!(to_ascii_punctuation("“a” ‘b’ c’s"), "\"a\" 'b' c's");
+    assert_eq!(to_ascii_punctuation("a – b — c…"), "a -- b --- c...");
+    assert_eq!(to_ascii_punctuation("é © «a»"), "é © «a»");
+  }
+}
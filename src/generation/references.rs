@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use super::common::*;
+
+/// Gets the key labels are matched by, which ignores casing and differences in
+/// whitespace (ex. `[Some  Label]` matches `[some label]`).
+pub fn get_label_key(label: &str) -> String {
+  label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// The keys of the labels that are referenced in a file.
+#[derive(Default)]
+pub struct ReferencedLabels {
+  pub footnotes: HashSet<String>,
+  pub links: HashSet<String>,
+}
+
+pub fn get_referenced_labels(nodes: &mut [Node], file_text: &str) -> ReferencedLabels {
+  let mut labels = ReferencedLabels::default();
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::FootnoteReference(reference) = node {
+      labels.footnotes.insert(get_label_key(&reference.name));
+    } else if let Some(label) = get_reference_label(node, file_text) {
+      labels.links.insert(get_label_key(label));
+    }
+  });
+  labels
+}
+
+/// Gets the label of a reference link or image (ex. `label` in `[text][label]`).
+pub fn get_reference_label<'a>(node: &'a Node, file_text: &'a str) -> Option<&'a str> {
+  match node {
+    Node::ReferenceLink(link) if !link.reference.is_empty() => Some(&link.reference),
+    Node::ReferenceImage(image) if !image.reference.is_empty() => Some(&image.reference),
+    // collapsed (ex. `[label][]`) and shortcut (ex. `[label]`) references use their text
+    Node::ReferenceLink(_) | Node::ShortcutLink(_) | Node::ReferenceImage(_) => {
+      let text = &file_text[node.range().clone()];
+      let text = text.strip_prefix('!').unwrap_or(text);
+      let text = text.strip_suffix("[]").unwrap_or(text);
+      text.strip_prefix('[')?.strip_suffix(']')
+    }
+    _ => None,
+  }
+}
+
+/// Gets the footnote references that don't have a definition (ex. `[^label]`)
+/// along with their labels. These are parsed as text.
+pub fn get_undefined_footnote_references(nodes: &mut [Node]) -> Vec<(Range, String)> {
+  let mut references = Vec::new();
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::Text(text) = node {
+      for (index, _) in text.text.match_indices("[^") {
+        let backslash_count = text.text[..index].chars().rev().take_while(|c| *c == '\\').count();
+        if backslash_count % 2 == 1 {
+          continue;
+        }
+        let label_text = &text.text[index + 2..];
+        if let Some(end) = label_text.find(']') {
+          let label = &label_text[..end];
+          if !label.is_empty() && !label.contains(|c: char| c.is_whitespace() || c == '[' || c == '^') {
+            let start = text.range.start + index;
+            references.push((start..start + end + 3, label.to_string()));
+          }
+        }
+      }
+    }
+  });
+  references
+}
+
+/// Removes the top-level footnote definitions and link reference definitions that
+/// aren't ignored and are never referenced.
+pub fn remove_unused_definitions(nodes: &mut Vec<Node>, file_text: &str, is_ignored: impl Fn(usize) -> bool) {
+  let labels = get_referenced_labels(nodes, file_text);
+  let mut index = 0;
+  nodes.retain_mut(|node| {
+    index += 1;
+    if is_ignored(index - 1) {
+      return true;
+    }
+    match node {
+      Node::FootnoteDefinition(definition) => labels.footnotes.contains(&get_label_key(&definition.name)),
+      Node::Paragraph(paragraph) if is_link_references_paragraph(paragraph) => {
+        paragraph.children.retain(|child| match child {
+          Node::LinkReference(reference) => labels.links.contains(&get_label_key(&reference.name)),
+          _ => true,
+        });
+        match (paragraph.children.first(), paragraph.children.last()) {
+          (Some(first), Some(last)) => {
+            paragraph.range = first.range().start..last.range().end;
+            true
+          }
+          _ => false,
+        }
+      }
+      _ => true,
+    }
+  });
+}
+
+/// Gets if the paragraph holds link reference definitions (ex. `[label]: url`).
+pub fn is_link_references_paragraph(paragraph: &Paragraph) -> bool {
+  !paragraph.children.is_empty()
+    && paragraph
+      .children
+      .iter()
+      .all(|child| matches!(child, Node::LinkReference(_)))
+}
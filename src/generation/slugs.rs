@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use super::common::*;
+use super::get_plain_text;
+use crate::configuration::SlugStyle;
+
+/// The anchor generated for a heading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadingSlug {
+  /// The byte range of the heading in the file text.
+  pub range: Range,
+  pub level: u32,
+  /// The text of the heading without markup.
+  pub text: String,
+  /// The anchor without the leading `#` (ex. `some-heading`).
+  pub slug: String,
+}
+
+/// Gets the slugs of all the headings in document order, using the heading's
+/// id attribute when it has one (ex. `{#custom-id}`).
+pub fn collect_heading_slugs(nodes: &mut [Node], file_text: &str, style: SlugStyle) -> Vec<HeadingSlug> {
+  let mut generator = SlugGenerator::new(style);
+  let mut slugs = Vec::new();
+  visit_nodes_mut(nodes, &mut |node| {
+    if let Node::Heading(heading) = node {
+      let text = get_plain_text(&heading.children, file_text).trim().to_string();
+      let slug = match heading.attributes.as_ref().and_then(|a| a.id.as_ref()) {
+        Some(id) => generator.add_custom(id),
+        None => generator.generate(&text),
+      };
+      slugs.push(HeadingSlug {
+        range: heading.range.start..heading.range.start + file_text[heading.range.clone()].trim_end().len(),
+        level: heading.level,
+        text,
+        slug,
+      });
+    }
+  });
+  slugs
+}
+
+/// Generates unique slugs in the style of a renderer.
+pub struct SlugGenerator {
+  style: SlugStyle,
+  occurrences: HashMap<String, usize>,
+}
+
+impl SlugGenerator {
+  pub fn new(style: SlugStyle) -> Self {
+    Self {
+      style,
+      occurrences: HashMap::new(),
+    }
+  }
+
+  /// Generates the slug for the text, adding a suffix when it was already used
+  /// (ex. `heading-1` or `heading_1` for MkDocs).
+  pub fn generate(&mut self, text: &str) -> String {
+    let slug = get_slug(text, self.style);
+    let mut unique_slug = slug.clone();
+    while self.occurrences.contains_key(&unique_slug) || (self.style == SlugStyle::Mkdocs && unique_slug.is_empty()) {
+      let count = self.occurrences.entry(slug.clone()).or_insert(0);
+      *count += 1;
+      unique_slug = match self.style {
+        SlugStyle::Github | SlugStyle::Gitlab => format!("{}-{}", slug, count),
+        SlugStyle::Mkdocs => format!("{}_{}", slug, count),
+      };
+    }
+    self.occurrences.insert(unique_slug.clone(), 0);
+    unique_slug
+  }
+
+  /// Marks a custom id as used so generated slugs won't conflict with it.
+  pub fn add_custom(&mut self, id: &str) -> String {
+    self.occurrences.entry(id.to_string()).or_insert(0);
+    id.to_string()
+  }
+}
+
+/// Gets the slug for the text without making it unique.
+pub fn get_slug(text: &str, style: SlugStyle) -> String {
+  static GITHUB_REMOVED_CHARS: OnceLock<Regex> = OnceLock::new();
+  static GITLAB_REMOVED_CHARS: OnceLock<Regex> = OnceLock::new();
+  static MKDOCS_REMOVED_CHARS: OnceLock<Regex> = OnceLock::new();
+  static MKDOCS_SEPARATORS: OnceLock<Regex> = OnceLock::new();
+
+  match style {
+    // github-slugger: remove punctuation and symbols, then replace each space with a hyphen
+    SlugStyle::Github => {
+      let removed_chars = GITHUB_REMOVED_CHARS.get_or_init(|| Regex::new(r"[^\p{L}\p{M}\p{N}\p{Pc}\- ]").unwrap());
+      removed_chars.replace_all(&text.to_lowercase(), "").replace(' ', "-")
+    }
+    // same as GitHub, but consecutive hyphens are collapsed
+    SlugStyle::Gitlab => {
+      let removed_chars = GITLAB_REMOVED_CHARS.get_or_init(|| Regex::new(r"[^\w\- ]").unwrap());
+      let slug = removed_chars.replace_all(&text.to_lowercase(), "").replace(' ', "-");
+      let mut result = String::with_capacity(slug.len());
+      for c in slug.chars() {
+        if c != '-' || !result.ends_with('-') {
+          result.push(c);
+        }
+      }
+      result
+    }
+    // Python-Markdown's slugify, which drops non-ASCII characters
+    SlugStyle::Mkdocs => {
+      let removed_chars = MKDOCS_REMOVED_CHARS.get_or_init(|| Regex::new(r"[^\w\s-]").unwrap());
+      let separators = MKDOCS_SEPARATORS.get_or_init(|| Regex::new(r"[-\s]+").unwrap());
+      let text = text.chars().filter(|c| c.is_ascii()).collect::<String>();
+      let text = removed_chars.replace_all(&text, "");
+      separators
+        .replace_all(text.trim().to_lowercase().as_str(), "-")
+        .to_string()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn it_should_get_github_slugs() {
+    let mut generator = SlugGenerator::new(SlugStyle::Github);
+    assert_eq!(generator.generate("Some Heading"), "some-heading");
+    assert_eq!(generator.generate("Some Heading"), "some-heading-1");
+    assert_eq!(generator.generate("Some Heading"), "some-heading-2");
+    assert_eq!(generator.generate("Some Heading-1"), "some-heading-1-1");
+    assert_eq!(generator.generate("What's new? (v1.0)"), "whats-new-v10");
+    assert_eq!(generator.generate("A  --  B"), "a------b");
+    assert_eq!(generator.generate("Café & snake_case"), "café--snake_case");
+  }
+
+  #[test]
+  fn it_should_get_gitlab_slugs() {
+    let mut generator = SlugGenerator::new(SlugStyle::Gitlab);
+    assert_eq!(generator.generate("A  --  B"), "a-b");
+    assert_eq!(generator.generate("A  --  B"), "a-b-1");
+    assert_eq!(generator.generate("Café & snake_case"), "café-snake_case");
+  }
+
+  #[test]
+  fn it_should_get_mkdocs_slugs() {
+    let mut generator = SlugGenerator::new(SlugStyle::Mkdocs);
+    assert_eq!(generator.generate(" A  --  B! "), "a-b");
+    assert_eq!(generator.generate("A B"), "a-b_1");
+    assert_eq!(generator.generate("A B"), "a-b_2");
+    assert_eq!(generator.generate("Café & snake_case"), "caf-snake_case");
+    assert_eq!(generator.generate("!!"), "_1");
+  }
+
+  #[test]
+  fn it_should_not_reuse_custom_ids() {
+    let mut generator = SlugGenerator::new(SlugStyle::Github);
+    assert_eq!(generator.add_custom("intro"), "intro");
+    assert_eq!(generator.generate("Intro"), "intro-1");
+  }
+}
@@ -0,0 +1,62 @@
+use super::HeadingSlug;
+
+/// Gets the markdown for a nested list of links to the headings
+/// (ex. `- [Heading](#heading)`).
+pub fn get_table_of_contents_text(headings: &[HeadingSlug]) -> String {
+  let mut text = String::new();
+  let mut parent_levels: Vec<u32> = Vec::new();
+  for heading in headings {
+    while parent_levels.last().is_some_and(|level| *level >= heading.level) {
+      parent_levels.pop();
+    }
+    text.push_str(&"  ".repeat(parent_levels.len()));
+    text.push_str("- [");
+    push_escaped(&mut text, &heading.text);
+    text.push_str("](#");
+    text.push_str(&heading.slug);
+    text.push_str(")\n");
+    parent_levels.push(heading.level);
+  }
+  text
+}
+
+fn push_escaped(result: &mut String, text: &str) {
+  for c in text.chars() {
+    if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '&') {
+      result.push('\\');
+    }
+    result.push(c);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn it_should_get_nested_list() {
+    let heading = |level: u32, text: &str, slug: &str| HeadingSlug {
+      range: 0..0,
+      level,
+      text: text.to_string(),
+      slug: slug.to_string(),
+    };
+    let headings = [
+      heading(2, "Install", "install"),
+      heading(4, "From [source]", "from-source"),
+      heading(3, "Options", "options"),
+      heading(2, "Usage", "usage"),
+      heading(1, "Other", "other"),
+    ];
+    assert_eq!(
+      get_table_of_contents_text(&headings),
+      concat!(
+        "- [Install](#install)\n",
+        "  - [From \\[source\\]](#from-source)\n",
+        "  - [Options](#options)\n",
+        "- [Usage](#usage)\n",
+        "- [Other](#other)\n",
+      )
+    );
+  }
+}
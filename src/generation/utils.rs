@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::sync::OnceLock;
 
 use regex::Regex;
 
@@ -52,6 +53,101 @@ pub fn has_leading_blankline(index: usize, text: &str) -> bool {
   false
 }
 
+/// Gets the tag of a code block from its info string, which is the text
+/// before any attributes (ex. `rust` for `rust,ignore`).
+pub fn get_code_block_tag(info: Option<&str>) -> Option<String> {
+  let tag = info?.chars().take_while(|&c| c != ' ' && c != ',').collect::<String>();
+  Some(tag)
+}
+
+/// Gets the number of blank lines before the provided index, where lines with
+/// only block quote markers are blank.
+pub fn get_leading_blank_line_count(index: usize, text: &str) -> u32 {
+  let mut newline_count: u32 = 0;
+  for c in text[0..index].chars().rev() {
+    if c == '\n' {
+      newline_count += 1;
+    } else if !c.is_whitespace() && c != '>' {
+      break;
+    }
+  }
+  newline_count.saturating_sub(1)
+}
+
+/// Gets if the HTML starts with an element whose blank lines are part of its
+/// content (ex. `<pre>`).
+pub fn is_raw_text_html(text: &str) -> bool {
+  let text = text.trim_start().to_ascii_lowercase();
+  ["<pre", "<script", "<style", "<textarea"]
+    .iter()
+    .any(|tag| text.starts_with(tag))
+}
+
+/// Gets the number of elements the HTML opens minus the number of elements it
+/// closes (ex. `1` for `<div class="a">` and `-1` for `</div>`).
+pub fn get_html_element_depth_change(text: &str) -> i32 {
+  static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+  const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+  ];
+
+  // comments, processing instructions, and declarations don't contain elements
+  if text.trim_start().starts_with("<!") || text.trim_start().starts_with("<?") {
+    return 0;
+  }
+
+  let tag_regex = TAG_REGEX.get_or_init(|| Regex::new(r"<(/?)([A-Za-z][A-Za-z0-9-]*)(?:[^>]*?)(/?)>").unwrap());
+  let mut depth_change = 0;
+  for captures in tag_regex.captures_iter(text) {
+    let is_closing = !captures[1].is_empty();
+    let is_self_closing = !captures[3].is_empty();
+    if is_closing {
+      depth_change -= 1;
+    } else if !is_self_closing && !VOID_ELEMENTS.contains(&captures[2].to_ascii_lowercase().as_str()) {
+      depth_change += 1;
+    }
+  }
+  depth_change
+}
+
+/// Removes the blank lines that exceed the maximum number of consecutive blank lines.
+pub fn limit_blank_lines(text: &str, max_blank_lines: u32) -> Cow<'_, str> {
+  let mut lines = Vec::new();
+  let mut blank_line_count = 0;
+  for line in text.split('\n') {
+    if line.trim().is_empty() {
+      blank_line_count += 1;
+      if blank_line_count > max_blank_lines {
+        continue;
+      }
+    } else {
+      blank_line_count = 0;
+    }
+    lines.push(line);
+  }
+  let result = lines.join("\n");
+  if result.len() == text.len() {
+    Cow::Borrowed(text)
+  } else {
+    Cow::Owned(result)
+  }
+}
+
+/// Gets if the provided index is at the start of a block's content in the text
+/// (ex. after any block quote, list item, task list, or footnote definition markers).
+pub fn is_block_content_start(index: usize, text: &str) -> bool {
+  let line_start = text[..index].rfind('\n').map(|i| i + 1).unwrap_or(0);
+  text[line_start..index]
+    .replace("[ ]", "[x]")
+    .split_whitespace()
+    .all(|token| {
+      token.chars().all(|c| c == '>')
+        || is_list_word(token)
+        || matches!(token, "[x]" | "[X]")
+        || token.starts_with("[^") && token.ends_with("]:")
+    })
+}
+
 pub fn file_has_ignore_file_directive(file_text: &str, directive_inner_text: &str) -> bool {
   let ignore_regex = get_ignore_comment_regex(directive_inner_text);
   ignore_regex.is_match(file_text)
@@ -138,6 +234,32 @@ fn is_space_tab_or_newline(c: char) -> bool {
 mod test {
   use super::*;
 
+  #[test]
+  fn it_should_limit_blank_lines() {
+    assert_eq!(
+      limit_blank_lines("<div>\n\n\n\na\n \n\n</div>", 1),
+      "<div>\n\na\n \n</div>"
+    );
+    assert_eq!(limit_blank_lines("<div>\n\n\na</div>", 0), "<div>\na</div>");
+    assert_eq!(limit_blank_lines("<div>\n\na</div>", 2), "<div>\n\na</div>");
+  }
+
+  #[test]
+  fn it_should_get_html_element_depth_change() {
+    assert_eq!(get_html_element_depth_change("<div class=\"a\">"), 1);
+    assert_eq!(get_html_element_depth_change("</div>"), -1);
+    assert_eq!(get_html_element_depth_change("<div><span>a</span>"), 1);
+    assert_eq!(get_html_element_depth_change("<p>a<br>b<img src=\"c\" /></p>"), 0);
+    assert_eq!(get_html_element_depth_change("<!-- <div> -->"), 0);
+  }
+
+  #[test]
+  fn it_should_get_leading_blank_line_count() {
+    assert_eq!(get_leading_blank_line_count(2, "a\nb"), 0);
+    assert_eq!(get_leading_blank_line_count(4, "a\n\n\nb"), 2);
+    assert_eq!(get_leading_blank_line_count(10, "> a\n>\n> \n> b"), 2);
+  }
+
   #[test]
   fn it_should_find_list_words() {
     assert_eq!(is_list_word("test"), false);
@@ -5,16 +5,28 @@
 #![allow(clippy::vec_init_then_push)]
 #![allow(clippy::type_complexity)]
 #![allow(clippy::needless_lifetimes)]
+#![allow(clippy::unnecessary_get_then_check)]
 #![deny(clippy::disallowed_methods)]
 #![deny(clippy::disallowed_types)]
 #![deny(clippy::print_stderr)]
 #![deny(clippy::print_stdout)]
 
+mod check_text;
 pub mod configuration;
+mod doc_comments;
 mod format_text;
 mod generation;
 
+pub use check_text::check_text;
+pub use check_text::get_heading_slugs;
+pub use check_text::Diagnostic;
+pub use check_text::DiagnosticSeverity;
+pub use doc_comments::format_jsdoc_comment;
+pub use doc_comments::format_rust_doc_comments;
+pub use format_text::format_mdx_text;
 pub use format_text::format_text;
+pub use format_text::format_text_range;
+pub use generation::HeadingSlug;
 
 #[cfg(feature = "tracing")]
 pub use format_text::trace_file;
@@ -40,6 +40,7 @@ impl SyncPluginHandler<Configuration> for MarkdownPluginHandler {
           "mkdn".to_string(),
           "mdown".to_string(),
           "markdown".to_string(),
+          "mdx".to_string(),
         ],
         file_names: vec![],
       },
@@ -76,7 +77,7 @@ impl SyncPluginHandler<Configuration> for MarkdownPluginHandler {
   ) -> FormatResult {
     let file_text = String::from_utf8(request.file_bytes)?;
     let config = request.config.clone();
-    return super::format_text(&file_text, request.config, |tag, file_text, line_width| {
+    let format_code_block_text = |tag: &str, file_text: &str, line_width: u32| -> anyhow::Result<Option<String>> {
       if let Some(ext) = tag_to_extension(tag, &config) {
         let file_path = PathBuf::from(format!("file.{}", ext));
         let mut additional_config = ConfigKeyMap::new();
@@ -96,8 +97,20 @@ impl SyncPluginHandler<Configuration> for MarkdownPluginHandler {
       } else {
         Ok(None)
       }
-    })
-    .map(|maybe_text| maybe_text.map(|t| t.into_bytes()));
+    };
+    let is_mdx = request
+      .file_path
+      .extension()
+      .map(|ext| ext.eq_ignore_ascii_case("mdx"))
+      .unwrap_or(false);
+    let result = match request.range {
+      // range formatting is only supported for markdown, so leave mdx files as-is
+      Some(_) if is_mdx => Ok(None),
+      None if is_mdx => super::format_mdx_text(&file_text, request.config, format_code_block_text),
+      Some(range) => super::format_text_range(&file_text, range, request.config, format_code_block_text),
+      None => super::format_text(&file_text, request.config, format_code_block_text),
+    };
+    return result.map(|maybe_text| maybe_text.map(|t| t.into_bytes()));
 
     fn tag_to_extension<'a>(tag: &str, config: &'a Configuration) -> Option<&'a str> {
       let tag_lower = tag.trim().to_lowercase();
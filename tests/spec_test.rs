@@ -24,19 +24,30 @@ fn main() {
     },
     {
       let global_config = global_config.clone();
-      Arc::new(move |_, file_text, spec_config| {
+      Arc::new(move |file_path, file_text, spec_config| {
         let spec_config: ConfigKeyMap = serde_json::from_value(spec_config.clone().into()).unwrap();
         let config_result = resolve_config(spec_config, &global_config);
         ensure_no_diagnostics(&config_result.diagnostics);
 
-        format_text(file_text, &config_result.config, |tag, file_text, line_width| {
+        let format_code_block_text = |tag: &str, file_text: &str, line_width: u32| {
           let end = format!("_formatted_{}", line_width);
           if tag == "format" && !file_text.ends_with(&end) {
             Ok(Some(format!("{}{}\n\n", file_text, end)))
           } else {
             Ok(None)
           }
-        })
+        };
+        if file_path.extension().is_some_and(|ext| ext == "mdx") {
+          format_mdx_text(file_text, &config_result.config, format_code_block_text)
+        } else if file_path.extension().is_some_and(|ext| ext == "js") {
+          // the file is a single comment block starting after the indentation
+          let comment_text = file_text.trim();
+          let indent_text = &file_text[..file_text.len() - file_text.trim_start().len()];
+          let result = format_jsdoc_comment(comment_text, indent_text, &config_result.config, format_code_block_text)?;
+          Ok(result.map(|text| format!("{}{}\n", indent_text, text)))
+        } else {
+          format_text(file_text, &config_result.config, format_code_block_text)
+        }
       })
     },
     Arc::new(move |_, _file_text, _spec_config| {